@@ -3,7 +3,9 @@
 extern crate test;
 use test::Bencher;
 
-use tfmicro::{MicroInterpreter, Model, MutableOpResolver};
+use tfmicro::{
+    InstantTicks, MicroInterpreter, Model, MutableOpResolver, OpProfiler,
+};
 
 use itertools::Itertools;
 use log::info;
@@ -32,14 +34,20 @@ fn person_detection(b: &mut Bencher) {
     let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
 
     let micro_op_resolver = MutableOpResolver::empty()
-        .depthwise_conv_2d()
-        .conv_2d()
-        .average_pool_2d();
+        .add_depthwise_conv_2d()
+        .add_conv_2d()
+        .add_average_pool_2d();
 
     // Build an interpreter to run the model with
-    let mut interpreter =
-        MicroInterpreter::new(&model, micro_op_resolver, &mut tensor_arena[..])
-            .unwrap();
+    let mut interpreter = MicroInterpreter::new_profiled(
+        &model,
+        micro_op_resolver,
+        &mut tensor_arena[..],
+    )
+    .unwrap();
+
+    // Time each operator of a single invoke
+    let mut profiler = OpProfiler::<_, 64>::new(InstantTicks::new());
 
     info!("Created setup");
 
@@ -48,10 +56,11 @@ fn person_detection(b: &mut Bencher) {
         // set input tensor
         interpreter.input(0, person).unwrap();
 
-        interpreter.invoke().unwrap();
+        profiler.clear();
+        interpreter.invoke_profiled(&mut profiler).unwrap();
 
         // get output tensor
-        let output_tensor = interpreter.output(0);
+        let output_tensor = interpreter.output(0).unwrap();
         assert_eq!(
             [1, 1, 1, 3],
             output_tensor.info().dims,
@@ -64,5 +73,10 @@ fn person_detection(b: &mut Bencher) {
         );
     });
 
+    for event in profiler.events() {
+        info!("{:>3} {:<20} {:>10} ns", event.node, event.tag, event.ticks);
+    }
+    info!("Total: {} ns", profiler.total_ticks());
+
     info!("---- Done");
 }
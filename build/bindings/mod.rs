@@ -89,8 +89,13 @@ pub fn bindgen_tflite_types() {
             .opaque_type("tflite::Model")
             .allowlist_type("tflite::MicroInterpreter")
             .opaque_type("tflite::MicroInterpreter")
+            .allowlist_type("tflite::BuiltinOperator")
+            .constified_enum_module("tflite::BuiltinOperator")
+            .allowlist_type("TFLMRegistration")
+            .opaque_type("TFLMRegistration")
             .allowlist_type("TfLiteStatus")
             .allowlist_type("TfLiteTensor")
+            .allowlist_type("TfLiteAffineQuantization")
             .allowlist_type("FrontendState")
             .allowlist_type("FrontendConfig")
             .allowlist_type("FrontendOutput")
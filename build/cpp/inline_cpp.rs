@@ -8,6 +8,7 @@ pub fn build_inline_cpp() {
 
     let mut builder = cpp_build::Config::new();
     builder.include(crate::paths::tflite_micro_submodule());
+    builder.include(crate::paths::csrc());
     for p in crate::paths::additional_include_dirs() {
         builder.include(p);
     }
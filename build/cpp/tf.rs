@@ -45,7 +45,8 @@ fn move_tf_to_out_dir_if_required() -> PathBuf {
 }
 
 /// Return a Vec of all *.cc files in `path`, excluding those that have a
-/// name containing 'test.cc'
+/// name containing 'test.cc'. `debug_log.cc` is also excluded, as `DebugLog`
+/// is implemented in src/debug_log.rs
 fn get_files_glob(path: PathBuf) -> Vec<String> {
     let mut paths: Vec<String> = vec![];
 
@@ -127,7 +128,6 @@ pub fn build_tensorflow_library() {
             .file(
                 tflite.join("tensorflow/lite/micro/tflite_bridge/micro_error_reporter.cc"),
             )
-            .file(tflite.join("tensorflow/lite/micro/debug_log.cc"))
             .file(tflite.join("tensorflow/lite/core/api/tensor_utils.cc"))
             .file(tflite.join("tensorflow/lite/kernels/internal/common.cc"))
             .file(tflite.join("tensorflow/lite/kernels/internal/portable_tensor_utils.cc"))
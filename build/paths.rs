@@ -8,6 +8,11 @@ pub fn manifest_dir() -> PathBuf {
     PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
 }
 
+/// C++ headers of this crate, used by the inline cpp
+pub fn csrc() -> PathBuf {
+    manifest_dir().join("csrc")
+}
+
 fn submodules() -> PathBuf {
     manifest_dir().join("submodules")
 }
@@ -0,0 +1,188 @@
+#ifndef TFMICRO_OP_RESOLVER_HPP_
+#define TFMICRO_OP_RESOLVER_HPP_
+
+#include <new>
+#include <string.h>
+
+#include "tensorflow/lite/micro/micro_log.h"
+#include "tensorflow/lite/micro/micro_mutable_op_resolver.h"
+#include "tensorflow/lite/micro/micro_op_resolver.h"
+#include "tensorflow/lite/schema/schema_generated.h"
+#include "tensorflow/lite/schema/schema_utils.h"
+
+// An op resolver with a capacity chosen at runtime, backing the Rust
+// `MutableOpResolver<N>` type.
+//
+// The registrations and their parsers are stored directly after this object,
+// in arrays sized by the Rust type. The object contains no pointers to
+// itself, so Rust is free to move it.
+//
+// Operators are added by registering them with a `MicroMutableOpResolver<1>`
+// and copying that registration here, so that only the kernels that are
+// actually used are linked.
+class TfMicroOpResolver : public tflite::MicroOpResolver {
+ public:
+  typedef tflite::TfLiteBridgeBuiltinParseFunction ParseFunction;
+
+  explicit TfMicroOpResolver(size_t capacity) : capacity_(capacity), len_(0) {}
+
+  const TFLMRegistration* FindOp(tflite::BuiltinOperator op) const override {
+    if (op == tflite::BuiltinOperator_CUSTOM) {
+      return nullptr;
+    }
+
+    for (size_t i = 0; i < len_; i++) {
+      if (registrations()[i].builtin_code == op) {
+        return &registrations()[i];
+      }
+    }
+    return nullptr;
+  }
+
+  const TFLMRegistration* FindOp(const char* op) const override {
+    for (size_t i = 0; i < len_; i++) {
+      const TFLMRegistration& registration = registrations()[i];
+      if (registration.builtin_code == tflite::BuiltinOperator_CUSTOM &&
+          strcmp(registration.custom_name, op) == 0) {
+        return &registration;
+      }
+    }
+    return nullptr;
+  }
+
+  ParseFunction GetOpDataParser(tflite::BuiltinOperator op) const override {
+    for (size_t i = 0; i < len_; i++) {
+      if (registrations()[i].builtin_code == op) {
+        return parsers()[i];
+      }
+    }
+    return nullptr;
+  }
+
+  // Copy the builtin operator registered with `staging`. Returns false if
+  // nothing was copied
+  bool AddFrom(const tflite::MicroMutableOpResolver<1>& staging) {
+    for (int32_t code = tflite::BuiltinOperator_MIN;
+         code <= tflite::BuiltinOperator_MAX; code++) {
+      tflite::BuiltinOperator op = static_cast<tflite::BuiltinOperator>(code);
+
+      const TFLMRegistration* registration = staging.FindOp(op);
+      if (registration != nullptr) {
+        return Add(*registration, staging.GetOpDataParser(op));
+      }
+    }
+    return false;
+  }
+
+  // Copy the custom operator `name` registered with `staging`. Returns false
+  // if nothing was copied
+  bool AddFrom(const tflite::MicroMutableOpResolver<1>& staging,
+               const char* name) {
+    const TFLMRegistration* registration = staging.FindOp(name);
+    return registration != nullptr && Add(*registration, nullptr);
+  }
+
+  // Add a custom operator. `name` must outlive this object. Returns false if
+  // nothing was added
+  bool AddCustom(const char* name, const TFLMRegistration* registration) {
+    TFLMRegistration custom = *registration;
+    custom.builtin_code = tflite::BuiltinOperator_CUSTOM;
+    custom.custom_name = name;
+    return Add(custom, nullptr);
+  }
+
+  size_t len() const { return len_; }
+
+  // Bytes needed for a copy made by `CopyFor(model, ...)`
+  static size_t SizeFor(const tflite::Model* model) {
+    auto codes = model->operator_codes();
+    size_t capacity = codes == nullptr ? 0 : codes->size();
+    return sizeof(TfMicroOpResolver) +
+           capacity * (sizeof(TFLMRegistration) + sizeof(ParseFunction));
+  }
+
+  // Copy the registrations used by `model` into `buffer`, of
+  // `SizeFor(model)` bytes
+  TfMicroOpResolver* CopyFor(const tflite::Model* model, void* buffer) const {
+    auto codes = model->operator_codes();
+    size_t capacity = codes == nullptr ? 0 : codes->size();
+    TfMicroOpResolver* copy = new (buffer) TfMicroOpResolver(capacity);
+
+    for (size_t n = 0; n < capacity; n++) {
+      auto opcode = codes->Get(n);
+      tflite::BuiltinOperator code = tflite::GetBuiltinCode(opcode);
+
+      const TFLMRegistration* registration = nullptr;
+      if (code != tflite::BuiltinOperator_CUSTOM) {
+        registration = FindOp(code);
+      } else if (opcode->custom_code() != nullptr) {
+        registration = FindOp(opcode->custom_code()->c_str());
+      }
+
+      // Operators used more than once are only copied once
+      if (registration != nullptr) {
+        copy->Add(*registration, GetOpDataParser(code));
+      }
+    }
+    return copy;
+  }
+
+  // The builtin code and custom name of the `n`th registration
+  void GetOp(size_t n, int32_t* code, const char** name) const {
+    *code = registrations()[n].builtin_code;
+    *name = registrations()[n].custom_name;
+  }
+
+ private:
+  bool Add(const TFLMRegistration& registration, ParseFunction parser) {
+    if (len_ >= capacity_) {
+      MicroPrintf("Couldn't register operator, op resolver is full.");
+      return false;
+    }
+
+    bool registered =
+        registration.builtin_code == tflite::BuiltinOperator_CUSTOM
+            ? FindOp(registration.custom_name) != nullptr
+            : FindOp(static_cast<tflite::BuiltinOperator>(
+                  registration.builtin_code)) != nullptr;
+    if (registered) {
+      return false;
+    }
+
+    registrations()[len_] = registration;
+    parsers()[len_] = parser;
+    len_++;
+    return true;
+  }
+
+  TFLMRegistration* registrations() {
+    return reinterpret_cast<TFLMRegistration*>(this + 1);
+  }
+  const TFLMRegistration* registrations() const {
+    return reinterpret_cast<const TFLMRegistration*>(this + 1);
+  }
+  ParseFunction* parsers() {
+    return reinterpret_cast<ParseFunction*>(registrations() + capacity_);
+  }
+  const ParseFunction* parsers() const {
+    return reinterpret_cast<const ParseFunction*>(registrations() +
+                                                  capacity_);
+  }
+
+  size_t capacity_;
+  size_t len_;
+};
+
+// The layout of `Inner<N>` in src/micro_op_resolver.rs must match
+static_assert(sizeof(TfMicroOpResolver) == 3 * sizeof(size_t),
+              "TfMicroOpResolver must be three words");
+static_assert(alignof(TfMicroOpResolver) == alignof(size_t),
+              "TfMicroOpResolver must be word aligned");
+static_assert(sizeof(TFLMRegistration) % alignof(size_t) == 0,
+              "Registrations must be a whole number of words");
+static_assert(alignof(TFLMRegistration) <= alignof(size_t),
+              "Registrations must be at most word aligned");
+static_assert(sizeof(TfMicroOpResolver::ParseFunction) == sizeof(size_t),
+              "Parse functions must be one word");
+
+#endif  // TFMICRO_OP_RESOLVER_HPP_
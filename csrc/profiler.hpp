@@ -0,0 +1,56 @@
+#ifndef TFMICRO_PROFILER_HPP_
+#define TFMICRO_PROFILER_HPP_
+
+#include <new>
+#include <stddef.h>
+#include <stdint.h>
+
+#include "tensorflow/lite/micro/micro_profiler_interface.h"
+
+typedef uint32_t (*TfMicroBeginEventFn)(void*, const char*, size_t);
+typedef void (*TfMicroEndEventFn)(void*, uint32_t);
+
+// Forwards profiling events from TensorFlow micro to a Rust `Profiler`,
+// whilst one is attached.
+//
+// TensorFlow micro doesn't pass the node being invoked, so events are
+// counted from when the profiler is attached.
+class TfMicroProfiler : public tflite::MicroProfilerInterface {
+ public:
+  TfMicroProfiler()
+      : profiler_(nullptr),
+        begin_event_(nullptr),
+        end_event_(nullptr),
+        node_(0) {}
+
+  uint32_t BeginEvent(const char* tag) override {
+    if (profiler_ == nullptr) {
+      return 0;
+    }
+    return begin_event_(profiler_, tag, node_++);
+  }
+
+  void EndEvent(uint32_t event_handle) override {
+    if (profiler_ != nullptr) {
+      end_event_(profiler_, event_handle);
+    }
+  }
+
+  void Attach(void* profiler, TfMicroBeginEventFn begin_event,
+              TfMicroEndEventFn end_event) {
+    profiler_ = profiler;
+    begin_event_ = begin_event;
+    end_event_ = end_event;
+    node_ = 0;
+  }
+
+  void Detach() { profiler_ = nullptr; }
+
+ private:
+  void* profiler_;
+  TfMicroBeginEventFn begin_event_;
+  TfMicroEndEventFn end_event_;
+  size_t node_;
+};
+
+#endif  // TFMICRO_PROFILER_HPP_
@@ -55,14 +55,14 @@ fn main() -> ! {
             .unwrap();
 
     // Check properties of the input sensor
-    assert_eq!([1, 49, 40, 1], interpreter.input_info(0).dims);
+    assert_eq!([1, 49, 40, 1], interpreter.input_info(0).unwrap().dims);
 
     // -------- 'yes' example --------
     interpreter.input(0, yes).unwrap();
     interpreter.invoke().unwrap();
 
     // Get output for 'yes'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     let silence_score: u8 = output_tensor.as_data()[0];
@@ -80,7 +80,7 @@ fn main() -> ! {
     interpreter.invoke().unwrap();
 
     // Get output for 'no'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     let silence_score: u8 = output_tensor.as_data()[0];
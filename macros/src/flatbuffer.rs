@@ -0,0 +1,189 @@
+//! A minimal reader for the parts of the TensorFlow Lite flatbuffer schema
+//! needed to find the operators used by a model
+//!
+//! See tensorflow/lite/schema/schema.fbs
+
+use std::convert::TryInto;
+
+/// `BuiltinOperator_CUSTOM` in the schema
+pub const BUILTIN_OPERATOR_CUSTOM: i32 = 32;
+
+/// An entry in the operator codes table of a model
+#[derive(Clone, PartialEq, Debug)]
+pub enum OperatorCode {
+    Builtin(i32),
+    Custom(String),
+}
+
+/// A table in the flatbuffer, at offset `pos`
+#[derive(Clone, Copy)]
+struct Table<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Result<u32, &'static str> {
+    buf.get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        .ok_or("flatbuffer offset out of range")
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> Result<u16, &'static str> {
+    buf.get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes(b.try_into().unwrap()))
+        .ok_or("flatbuffer offset out of range")
+}
+
+/// Follow the unsigned offset stored at `pos`
+fn follow(buf: &[u8], pos: usize) -> Result<usize, &'static str> {
+    Ok(pos + read_u32(buf, pos)? as usize)
+}
+
+impl<'a> Table<'a> {
+    fn root(buf: &'a [u8]) -> Result<Self, &'static str> {
+        Ok(Self {
+            buf,
+            pos: follow(buf, 0)?,
+        })
+    }
+
+    /// Returns the position of field number `field`, or `None` if the field
+    /// is not present
+    fn field(&self, field: usize) -> Result<Option<usize>, &'static str> {
+        let soffset = read_u32(self.buf, self.pos)? as i32;
+        let vtable = self.pos as i64 - soffset as i64;
+        if vtable < 0 {
+            return Err("flatbuffer vtable out of range");
+        }
+        let vtable = vtable as usize;
+        let vtable_len = read_u16(self.buf, vtable)? as usize;
+
+        let entry = 4 + 2 * field;
+        if entry + 2 > vtable_len {
+            return Ok(None);
+        }
+
+        match read_u16(self.buf, vtable + entry)? {
+            0 => Ok(None),
+            offset => Ok(Some(self.pos + offset as usize)),
+        }
+    }
+
+    fn i8_field(&self, field: usize, default: i8) -> Result<i8, &'static str> {
+        Ok(match self.field(field)? {
+            Some(pos) => self
+                .buf
+                .get(pos)
+                .map(|b| *b as i8)
+                .ok_or("flatbuffer offset out of range")?,
+            None => default,
+        })
+    }
+
+    fn i32_field(
+        &self,
+        field: usize,
+        default: i32,
+    ) -> Result<i32, &'static str> {
+        Ok(match self.field(field)? {
+            Some(pos) => read_u32(self.buf, pos)? as i32,
+            None => default,
+        })
+    }
+
+    fn str_field(&self, field: usize) -> Result<Option<&'a str>, &'static str> {
+        let pos = match self.field(field)? {
+            Some(pos) => follow(self.buf, pos)?,
+            None => return Ok(None),
+        };
+        let len = read_u32(self.buf, pos)? as usize;
+
+        let bytes = self
+            .buf
+            .get(pos + 4..pos + 4 + len)
+            .ok_or("flatbuffer string out of range")?;
+        std::str::from_utf8(bytes)
+            .map(Some)
+            .or(Err("flatbuffer string is not valid UTF8"))
+    }
+
+    fn tables_field(
+        &self,
+        field: usize,
+    ) -> Result<Vec<Table<'a>>, &'static str> {
+        let pos = match self.field(field)? {
+            Some(pos) => follow(self.buf, pos)?,
+            None => return Ok(vec![]),
+        };
+        let len = read_u32(self.buf, pos)? as usize;
+
+        (0..len)
+            .map(|n| {
+                Ok(Table {
+                    buf: self.buf,
+                    pos: follow(self.buf, pos + 4 + 4 * n)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the operator codes table of the model in `buf`, in order
+pub fn operator_codes(buf: &[u8]) -> Result<Vec<OperatorCode>, &'static str> {
+    // Model.operator_codes
+    let model = Table::root(buf)?;
+
+    model
+        .tables_field(1)?
+        .into_iter()
+        .map(|opcode| {
+            // OperatorCode.deprecated_builtin_code and
+            // OperatorCode.builtin_code. Like `tflite::GetBuiltinCode`, take
+            // the larger of the two
+            let deprecated = opcode.i8_field(0, 0)? as i32;
+            let code = opcode.i32_field(3, 0)?.max(deprecated);
+
+            if code == BUILTIN_OPERATOR_CUSTOM {
+                // OperatorCode.custom_code
+                let name = opcode
+                    .str_field(1)?
+                    .ok_or("custom operator without a name")?;
+                Ok(OperatorCode::Custom(name.to_string()))
+            } else {
+                Ok(OperatorCode::Builtin(code))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operator_codes_in_order() {
+        // Written by tests/models/make_models.py in the tfmicro crate
+        let model = include_bytes!("../tests/models/operators.tflite");
+
+        let codes = operator_codes(&model[..]).unwrap();
+
+        // DEPTHWISE_CONV_2D, FULLY_CONNECTED, SOFTMAX, RESHAPE, then
+        // VAR_HANDLE, which only fits in the extended builtin code
+        assert_eq!(
+            codes,
+            [
+                OperatorCode::Builtin(4),
+                OperatorCode::Builtin(9),
+                OperatorCode::Builtin(25),
+                OperatorCode::Builtin(22),
+                OperatorCode::Builtin(142),
+                OperatorCode::Custom("SCALE".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_model() {
+        assert!(operator_codes(&[0xff; 8]).is_err());
+    }
+}
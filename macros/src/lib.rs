@@ -0,0 +1,214 @@
+//! Procedural macros for [tfmicro](https://crates.io/crates/tfmicro)
+//!
+//! These are re-exported by `tfmicro` and should be used from there.
+
+extern crate proc_macro;
+
+mod flatbuffer;
+mod operators;
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Ident, LitStr};
+
+use flatbuffer::OperatorCode;
+use operators::{Code, Operator, OPERATORS};
+
+/// Returns the operator for an operator code
+fn operator_for(code: &OperatorCode) -> Result<&'static Operator, String> {
+    match code {
+        OperatorCode::Builtin(code) => operators::find_builtin(*code)
+            .ok_or_else(|| {
+                format!("tfmicro has no binding for builtin operator {}", code)
+            }),
+        OperatorCode::Custom(name) => {
+            operators::find_custom(name).ok_or_else(|| {
+                format!("tfmicro has no binding for custom operator {}", name)
+            })
+        }
+    }
+}
+
+/// Expands to a `MutableOpResolver` containing exactly the operators used by
+/// a `.tflite` model. The path is relative to the `Cargo.toml` of the crate
+/// invoking the macro.
+///
+/// See `tfmicro::op_resolver_for`.
+#[proc_macro]
+pub fn op_resolver_for(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+
+    match expand(&path) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => syn::Error::new(path.span(), e).to_compile_error().into(),
+    }
+}
+
+fn expand(path: &LitStr) -> Result<proc_macro2::TokenStream, String> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+        .map_err(|e| format!("CARGO_MANIFEST_DIR: {}", e))?;
+    let model_path = PathBuf::from(manifest_dir).join(path.value());
+
+    let model = fs::read(&model_path).map_err(|e| {
+        format!("Couldn't read {}: {}", model_path.display(), e)
+    })?;
+    let codes = flatbuffer::operator_codes(&model).map_err(|e| {
+        format!("Couldn't parse {}: {}", model_path.display(), e)
+    })?;
+
+    // Each version of an operator has an entry in the operator codes table,
+    // but may only be registered once
+    let mut seen = BTreeSet::new();
+    let mut adds = vec![];
+    for code in &codes {
+        let op = operator_for(code)?;
+        if !seen.insert(op.method) {
+            continue;
+        }
+
+        // Whether the `cpp-std` feature is enabled is only known to
+        // tfmicro, which fails with a clear message if it isn't
+        let method = Ident::new(op.method, Span::call_site());
+        adds.push(if op.cpp_std {
+            let name = match op.code {
+                Code::Builtin(_, name) | Code::Custom(name) => name,
+            };
+            quote! {
+                ::tfmicro::__cpp_std_operator!(#name, resolver.#method())
+            }
+        } else {
+            quote! { resolver.#method() }
+        });
+    }
+
+    // Referencing the model with `include_bytes!` makes cargo rebuild the
+    // caller whenever the model changes. The resolver is sized for exactly
+    // the operators it contains
+    let model_path = model_path.to_string_lossy();
+    let capacity = adds.len();
+    Ok(quote! {
+        {
+            const _: &[u8] = include_bytes!(#model_path);
+
+            let resolver = ::tfmicro::MutableOpResolver::<#capacity>::new();
+            #( let resolver = #adds; )*
+            resolver
+        }
+    })
+}
+
+/// Expands to the conversions between `tfmicro::Op` and operator codes, and
+/// `MutableOpResolver::add`, from the list in `operators`
+///
+/// Only for use in tfmicro's `operators` module.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __operator_table(_input: TokenStream) -> TokenStream {
+    let ident = |s: &str| Ident::new(s, Span::call_site());
+
+    let mut builtin_variants = vec![];
+    let mut builtin_codes = vec![];
+    let mut custom_variants = vec![];
+    let mut custom_names = vec![];
+    let mut cpp_std_variants = vec![];
+    let mut add_arms = vec![];
+    for op in OPERATORS {
+        let variant = ident(op.variant);
+        let method = ident(op.method);
+
+        match op.code {
+            Code::Builtin(_, name) => {
+                builtin_variants.push(variant.clone());
+                builtin_codes.push(ident(&format!("BuiltinOperator_{}", name)));
+            }
+            Code::Custom(name) => {
+                custom_variants.push(variant.clone());
+                custom_names.push(name);
+            }
+        }
+
+        if op.cpp_std {
+            cpp_std_variants.push(variant.clone());
+            add_arms.push(quote! {
+                #[cfg(feature = "cpp-std")]
+                Op::#variant => self.#method(),
+            });
+        } else {
+            add_arms.push(quote! { Op::#variant => self.#method(), });
+        }
+    }
+
+    let tokens = quote! {
+        impl Op {
+            /// The operator registered with builtin `code`, or `None` if
+            /// there is no binding for this operator
+            fn from_builtin_code(code: BuiltinOperator::Type) -> Option<Self> {
+                use BuiltinOperator::*;
+
+                Some(match code {
+                    #( #builtin_codes => Op::#builtin_variants, )*
+                    _ => return None,
+                })
+            }
+
+            /// The custom operator registered by TensorFlow micro under
+            /// `name`, or `None` if there is no binding for this operator
+            pub fn from_custom_name(name: &str) -> Option<Self> {
+                Some(match name {
+                    #( #custom_names => Op::#custom_variants, )*
+                    _ => return None,
+                })
+            }
+
+            /// Returns whether this operator can be passed to
+            /// [`MutableOpResolver::add`](crate::MutableOpResolver::add)
+            pub fn is_supported(&self) -> bool {
+                match self {
+                    Op::Custom(_) => false,
+                    #( Op::#cpp_std_variants )|* => cfg!(feature = "cpp-std"),
+                    _ => true,
+                }
+            }
+        }
+
+        /// Convert to a builtin operator code in the flatbuffer schema.
+        /// Custom operators have the code `BuiltinOperator_CUSTOM`
+        impl From<Op> for BuiltinOperator::Type {
+            fn from(op: Op) -> Self {
+                use BuiltinOperator::*;
+
+                match op {
+                    #( Op::#builtin_variants => #builtin_codes, )*
+                    _ => BuiltinOperator_CUSTOM,
+                }
+            }
+        }
+
+        // Data-driven construction, used by `MutableOpResolver::for_model`
+        impl<const N: usize> MutableOpResolver<N> {
+            /// Use `op` in this op resolver
+            ///
+            /// Unlike calling the `add_*` methods directly, this links every
+            /// kernel into the binary.
+            ///
+            /// # Panics
+            ///
+            /// Panics for `Op::Custom`, which must be added with
+            /// [`add_custom`](Self::add_custom), and for those operators that
+            /// require the `cpp-std` feature when it is disabled.
+            pub fn add(self, op: Op) -> Self {
+                match op {
+                    #( #add_arms )*
+                    _ => panic!("{:?} can't be added to this op resolver.", op),
+                }
+            }
+        }
+    };
+    tokens.into()
+}
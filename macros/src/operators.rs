@@ -0,0 +1,299 @@
+//! The operators that have bindings in tfmicro
+//!
+//! This is the only list of them. `op_resolver_for!` uses it to find the
+//! `MutableOpResolver` method for each operator code in a model, and tfmicro
+//! generates the conversions between `Op` and operator codes from it with
+//! `__operator_table!`. So a binding added to one is added to both.
+
+/// How an operator is identified in a model
+pub enum Code {
+    /// A builtin operator, with its code and name in the schema
+    Builtin(i32, &'static str),
+    /// A custom operator, with the name TensorFlow micro registers
+    Custom(&'static str),
+}
+
+/// An operator that has a binding in tfmicro
+pub struct Operator {
+    /// The variant of `Op`
+    pub variant: &'static str,
+    /// The `MutableOpResolver` method
+    pub method: &'static str,
+    pub code: Code,
+    /// The binding requires the `cpp-std` feature of tfmicro
+    pub cpp_std: bool,
+}
+
+const fn builtin(
+    variant: &'static str,
+    method: &'static str,
+    code: i32,
+    name: &'static str,
+) -> Operator {
+    Operator {
+        variant,
+        method,
+        code: Code::Builtin(code, name),
+        cpp_std: false,
+    }
+}
+
+const fn custom(
+    variant: &'static str,
+    method: &'static str,
+    name: &'static str,
+    cpp_std: bool,
+) -> Operator {
+    Operator {
+        variant,
+        method,
+        code: Code::Custom(name),
+        cpp_std,
+    }
+}
+
+pub const OPERATORS: &[Operator] = &[
+    builtin("Abs", "add_abs", 101, "ABS"),
+    builtin("Add", "add_add", 0, "ADD"),
+    builtin("AddN", "add_add_n", 106, "ADD_N"),
+    builtin("ArgMax", "add_argmax", 56, "ARG_MAX"),
+    builtin("ArgMin", "add_argmin", 79, "ARG_MIN"),
+    builtin(
+        "AssignVariable",
+        "add_assign_variable",
+        144,
+        "ASSIGN_VARIABLE",
+    ),
+    builtin("AveragePool2D", "add_average_pool_2d", 1, "AVERAGE_POOL_2D"),
+    builtin("BatchMatMul", "add_batch_mat_mul", 126, "BATCH_MATMUL"),
+    builtin(
+        "BatchToSpaceNd",
+        "add_batch_to_space_nd",
+        37,
+        "BATCH_TO_SPACE_ND",
+    ),
+    builtin("BroadcastArgs", "add_broadcast_args", 145, "BROADCAST_ARGS"),
+    builtin("BroadcastTo", "add_broadcast_to", 130, "BROADCAST_TO"),
+    builtin("CallOnce", "add_call_once", 129, "CALL_ONCE"),
+    builtin("Cast", "add_cast", 53, "CAST"),
+    builtin("Ceil", "add_ceil", 104, "CEIL"),
+    custom(
+        "CircularBuffer",
+        "add_circular_buffer",
+        "CIRCULAR_BUFFER",
+        true,
+    ),
+    builtin("Concatenation", "add_concatenation", 2, "CONCATENATION"),
+    builtin("Conv2D", "add_conv_2d", 3, "CONV_2D"),
+    builtin("Cos", "add_cos", 108, "COS"),
+    builtin("CumSum", "add_cum_sum", 128, "CUMSUM"),
+    custom("Delay", "add_delay", "SignalDelay", true),
+    builtin("DepthToSpace", "add_depth_to_space", 5, "DEPTH_TO_SPACE"),
+    builtin(
+        "DepthwiseConv2D",
+        "add_depthwise_conv_2d",
+        4,
+        "DEPTHWISE_CONV_2D",
+    ),
+    builtin("Dequantize", "add_dequantize", 6, "DEQUANTIZE"),
+    custom(
+        "DetectionPostprocess",
+        "add_detection_postprocess",
+        "TFLite_Detection_PostProcess",
+        true,
+    ),
+    builtin("Div", "add_div", 42, "DIV"),
+    builtin(
+        "EmbeddingLookup",
+        "add_embedding_lookup",
+        7,
+        "EMBEDDING_LOOKUP",
+    ),
+    custom("Energy", "add_energy", "SignalEnergy", true),
+    builtin("Elu", "add_elu", 111, "ELU"),
+    builtin("Equal", "add_equal", 71, "EQUAL"),
+    custom("EthosU", "add_ethos_u", "ethos-u", false),
+    builtin("Exp", "add_exp", 47, "EXP"),
+    builtin("ExpandDims", "add_expand_dims", 70, "EXPAND_DIMS"),
+    custom(
+        "FftAutoScale",
+        "add_fft_auto_scale",
+        "SignalFftAutoScale",
+        true,
+    ),
+    builtin("Fill", "add_fill", 94, "FILL"),
+    custom("FilterBank", "add_filter_bank", "SignalFilterBank", true),
+    custom(
+        "FilterBankLog",
+        "add_filter_bank_log",
+        "SignalFilterBankLog",
+        true,
+    ),
+    custom(
+        "FilterBankSquareRoot",
+        "add_filter_bank_square_root",
+        "SignalFilterBankSquareRoot",
+        true,
+    ),
+    custom(
+        "FilterBankSpectralSubtraction",
+        "add_filter_bank_spectral_subtraction",
+        "SignalFilterBankSpectralSubtraction",
+        true,
+    ),
+    builtin("Floor", "add_floor", 8, "FLOOR"),
+    builtin("FloorDiv", "add_floor_div", 90, "FLOOR_DIV"),
+    builtin("FloorMod", "add_floor_mod", 95, "FLOOR_MOD"),
+    custom("Framer", "add_framer", "SignalFramer", true),
+    builtin(
+        "FullyConnected",
+        "add_fully_connected",
+        9,
+        "FULLY_CONNECTED",
+    ),
+    builtin("Gather", "add_gather", 36, "GATHER"),
+    builtin("GatherNd", "add_gather_nd", 107, "GATHER_ND"),
+    builtin("Greater", "add_greater", 61, "GREATER"),
+    builtin("GreaterEqual", "add_greater_equal", 62, "GREATER_EQUAL"),
+    builtin("HardSwish", "add_hard_swish", 117, "HARD_SWISH"),
+    builtin("If", "add_if", 118, "IF"),
+    custom("Irfft", "add_irfft", "SignalIrfft", true),
+    builtin(
+        "L2Normalization",
+        "add_l2_normalization",
+        11,
+        "L2_NORMALIZATION",
+    ),
+    builtin("L2Pool2D", "add_l2_pool_2d", 12, "L2_POOL_2D"),
+    builtin("LeakyRelu", "add_leaky_relu", 98, "LEAKY_RELU"),
+    builtin("Less", "add_less", 58, "LESS"),
+    builtin("LessEqual", "add_less_equal", 63, "LESS_EQUAL"),
+    builtin("Log", "add_log", 73, "LOG"),
+    builtin("LogicalAnd", "add_logical_and", 86, "LOGICAL_AND"),
+    builtin("LogicalNot", "add_logical_not", 87, "LOGICAL_NOT"),
+    builtin("LogicalOr", "add_logical_or", 84, "LOGICAL_OR"),
+    builtin("Logistic", "add_logistic", 14, "LOGISTIC"),
+    builtin("LogSoftmax", "add_log_softmax", 50, "LOG_SOFTMAX"),
+    builtin("Maximum", "add_maximum", 55, "MAXIMUM"),
+    builtin("MaxPool2D", "add_max_pool_2d", 17, "MAX_POOL_2D"),
+    builtin("MirrorPad", "add_mirror_pad", 100, "MIRROR_PAD"),
+    builtin("Mean", "add_mean", 40, "MEAN"),
+    builtin("Minimum", "add_minimum", 57, "MINIMUM"),
+    builtin("Mul", "add_mul", 18, "MUL"),
+    builtin("Neg", "add_neg", 59, "NEG"),
+    builtin("NotEqual", "add_not_equal", 72, "NOT_EQUAL"),
+    custom("OverlapAdd", "add_overlap_add", "SignalOverlapAdd", true),
+    builtin("Pack", "add_pack", 83, "PACK"),
+    builtin("PadV2", "add_pad_v2", 60, "PADV2"),
+    custom("Pcan", "add_pcan", "SignalPCAN", true),
+    builtin("Prelu", "add_prelu", 54, "PRELU"),
+    builtin("Quantize", "add_quantize", 114, "QUANTIZE"),
+    builtin("ReadVariable", "add_read_variable", 143, "READ_VARIABLE"),
+    builtin("ReduceMax", "add_reduce_max", 82, "REDUCE_MAX"),
+    builtin("Relu", "add_relu", 19, "RELU"),
+    builtin("Relu6", "add_relu6", 21, "RELU6"),
+    builtin("Reshape", "add_reshape", 22, "RESHAPE"),
+    builtin(
+        "ResizeBilinear",
+        "add_resize_bilinear",
+        23,
+        "RESIZE_BILINEAR",
+    ),
+    builtin(
+        "ResizeNearestNeighbor",
+        "add_resize_nearest_neighbor",
+        97,
+        "RESIZE_NEAREST_NEIGHBOR",
+    ),
+    custom("Rfft", "add_rfft", "SignalRfft", true),
+    builtin("Round", "add_round", 116, "ROUND"),
+    builtin("Rsqrt", "add_rsqrt", 76, "RSQRT"),
+    builtin("SelectV2", "add_select_v2", 123, "SELECT_V2"),
+    builtin("Shape", "add_shape", 77, "SHAPE"),
+    builtin("Sin", "add_sin", 66, "SIN"),
+    builtin("Slice", "add_slice", 65, "SLICE"),
+    builtin("Softmax", "add_softmax", 25, "SOFTMAX"),
+    builtin(
+        "SpaceToBatchNd",
+        "add_space_to_batch_nd",
+        38,
+        "SPACE_TO_BATCH_ND",
+    ),
+    builtin("SpaceToDepth", "add_space_to_depth", 26, "SPACE_TO_DEPTH"),
+    builtin("Split", "add_split", 49, "SPLIT"),
+    builtin("SplitV", "add_split_v", 102, "SPLIT_V"),
+    builtin("Squeeze", "add_squeeze", 43, "SQUEEZE"),
+    builtin("Sqrt", "add_sqrt", 75, "SQRT"),
+    builtin("Square", "add_square", 92, "SQUARE"),
+    builtin(
+        "SquaredDifference",
+        "add_squared_difference",
+        99,
+        "SQUARED_DIFFERENCE",
+    ),
+    builtin("StridedSlice", "add_strided_slice", 45, "STRIDED_SLICE"),
+    custom("Stacker", "add_stacker", "SignalStacker", true),
+    builtin("Sub", "add_sub", 41, "SUB"),
+    builtin("Sum", "add_sum", 74, "SUM"),
+    builtin("Svdf", "add_svdf", 27, "SVDF"),
+    builtin("Tanh", "add_tanh", 28, "TANH"),
+    builtin("TransposeConv", "add_transpose_conv", 67, "TRANSPOSE_CONV"),
+    builtin("Unpack", "add_unpack", 88, "UNPACK"),
+    builtin(
+        "UnidirectionalSequenceLSTM",
+        "add_unidirectional_sequence_lstm",
+        44,
+        "UNIDIRECTIONAL_SEQUENCE_LSTM",
+    ),
+    builtin("VarHandle", "add_var_handle", 142, "VAR_HANDLE"),
+    builtin("While", "add_while", 119, "WHILE"),
+    custom("Window", "add_window", "SignalWindow", true),
+    builtin("ZerosLike", "add_zeros_like", 93, "ZEROS_LIKE"),
+];
+
+/// Returns the operator with `code` in a model
+pub fn find_builtin(code: i32) -> Option<&'static Operator> {
+    OPERATORS.iter().find(|op| match op.code {
+        Code::Builtin(c, _) => c == code,
+        Code::Custom(_) => false,
+    })
+}
+
+/// Returns the custom operator registered as `name`
+pub fn find_custom(name: &str) -> Option<&'static Operator> {
+    OPERATORS.iter().find(|op| match op.code {
+        Code::Builtin(..) => false,
+        Code::Custom(n) => n == name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn unique() {
+        let mut variants = BTreeSet::new();
+        let mut methods = BTreeSet::new();
+        let mut codes = BTreeSet::new();
+        for op in OPERATORS {
+            assert!(variants.insert(op.variant), "{}", op.variant);
+            assert!(methods.insert(op.method), "{}", op.method);
+            let code = match op.code {
+                Code::Builtin(code, _) => code.to_string(),
+                Code::Custom(name) => name.to_string(),
+            };
+            assert!(codes.insert(code), "{}", op.variant);
+        }
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(find_builtin(9).unwrap().method, "add_fully_connected");
+        assert_eq!(find_custom("SignalDelay").unwrap().method, "add_delay");
+        assert!(find_custom("SignalDelay").unwrap().cpp_std);
+        assert!(find_builtin(32).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+//! Statistics on the use of the tensor arena
+//!
+//! Create an interpreter with
+//! [`MicroInterpreter::new_recording`](crate::MicroInterpreter::new_recording)
+//! and call [`arena_stats`](crate::MicroInterpreter::arena_stats) to see
+//! which allocations use the arena.
+
+use crate::micro_interpreter::{MicroInterpreter, Options};
+use crate::micro_op_resolver::OpResolver;
+use crate::model::Model;
+use crate::Error;
+
+cpp! {{
+    #include "tensorflow/lite/micro/recording_micro_allocator.h"
+}}
+
+/// Allocations of one type in the tensor arena
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct RecordedAllocation {
+    /// Bytes requested
+    pub requested_bytes: usize,
+    /// Bytes used, including alignment padding
+    pub used_bytes: usize,
+    /// Number of allocations
+    pub count: usize,
+}
+
+/// A breakdown of the tensor arena by type of allocation
+///
+/// TensorFlow micro does not record scratch buffers separately. They are
+/// part of [`used_bytes`](Self::used_bytes), but not of any category.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct ArenaStats {
+    /// Total bytes used in the arena, as
+    /// [`arena_used_bytes`](crate::MicroInterpreter::arena_used_bytes)
+    pub used_bytes: usize,
+    /// Bytes used at the head of the arena, for non-persistent buffers
+    /// such as activation tensors and scratch buffers. These are planned
+    /// to overlap, so this is often the largest part of the arena
+    pub non_persistent_bytes: usize,
+    /// `TfLiteEvalTensor` structs
+    pub eval_tensor_data: RecordedAllocation,
+    /// `TfLiteTensor` structs that persist for the lifetime of the
+    /// interpreter
+    pub persistent_tensor_data: RecordedAllocation,
+    /// Quantization parameters of persistent tensors
+    pub persistent_tensor_quantization_data: RecordedAllocation,
+    /// Buffers allocated by kernels with `AllocatePersistentBuffer`
+    pub persistent_buffer_data: RecordedAllocation,
+    /// Data of variable tensors
+    pub variable_tensor_data: RecordedAllocation,
+    /// Node and registration arrays for each subgraph
+    pub node_and_registration_array: RecordedAllocation,
+    /// Builtin data of each operator
+    pub op_data: RecordedAllocation,
+}
+
+/// Type of allocation, as `tflite::RecordedAllocationType`
+#[derive(Clone, Copy)]
+enum AllocationType {
+    EvalTensorData,
+    PersistentTensorData,
+    PersistentTensorQuantizationData,
+    PersistentBufferData,
+    VariableTensorData,
+    NodeAndRegistrationArray,
+    OpData,
+}
+
+impl<'a> MicroInterpreter<'a> {
+    /// Create a new micro_interpreter that records each allocation in the
+    /// tensor arena, see [`arena_stats`](Self::arena_stats)
+    ///
+    /// Recording uses a little more of the arena than
+    /// [`new`](Self::new), so this is intended for development.
+    ///
+    /// # Errors
+    ///
+    /// As for [`new`](Self::new).
+    pub fn new_recording<'m: 'a, R: OpResolver>(
+        model: &'m Model,
+        resolver: R,
+        tensor_arena: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        Self::create(
+            model,
+            &resolver,
+            tensor_arena,
+            Options {
+                recording: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Returns a breakdown of the tensor arena by type of allocation, or
+    /// `None` if this interpreter was not created with
+    /// [`new_recording`](Self::new_recording)
+    pub fn arena_stats(&self) -> Option<ArenaStats> {
+        if self.recording_allocator.is_null() {
+            return None;
+        }
+
+        Some(ArenaStats {
+            used_bytes: self.arena_used_bytes(),
+            non_persistent_bytes: self.non_persistent_bytes(),
+            eval_tensor_data: self.recorded(AllocationType::EvalTensorData),
+            persistent_tensor_data: self
+                .recorded(AllocationType::PersistentTensorData),
+            persistent_tensor_quantization_data: self
+                .recorded(AllocationType::PersistentTensorQuantizationData),
+            persistent_buffer_data: self
+                .recorded(AllocationType::PersistentBufferData),
+            variable_tensor_data: self
+                .recorded(AllocationType::VariableTensorData),
+            node_and_registration_array: self
+                .recorded(AllocationType::NodeAndRegistrationArray),
+            op_data: self.recorded(AllocationType::OpData),
+        })
+    }
+
+    /// Returns the bytes used at the head of the arena
+    fn non_persistent_bytes(&self) -> usize {
+        let allocator = self.recording_allocator;
+
+        unsafe {
+            cpp!([allocator as "const tflite::RecordingMicroAllocator*"]
+                  -> usize as "size_t" {
+                return allocator->GetSimpleMemoryAllocator()
+                    ->GetNonPersistentUsedBytes();
+            })
+        }
+    }
+
+    /// Returns the allocations of type `allocation_type`
+    fn recorded(&self, allocation_type: AllocationType) -> RecordedAllocation {
+        let allocator = self.recording_allocator;
+        let allocation_type = allocation_type as u32;
+        let mut recorded = RecordedAllocation::default();
+
+        unsafe {
+            let requested_bytes = &mut recorded.requested_bytes;
+            let used_bytes = &mut recorded.used_bytes;
+            let count = &mut recorded.count;
+
+            cpp!([allocator as "const tflite::RecordingMicroAllocator*",
+                  allocation_type as "uint32_t",
+                  requested_bytes as "size_t*",
+                  used_bytes as "size_t*",
+                  count as "size_t*"] {
+                using tflite::RecordedAllocationType;
+
+                // In the order of `AllocationType`
+                const RecordedAllocationType types[] = {
+                    RecordedAllocationType::kTfLiteEvalTensorData,
+                    RecordedAllocationType::kPersistentTfLiteTensorData,
+                    RecordedAllocationType::kPersistentTfLiteTensorQuantizationData,
+                    RecordedAllocationType::kPersistentBufferData,
+                    RecordedAllocationType::kTfLiteTensorVariableBufferData,
+                    RecordedAllocationType::kNodeAndRegistrationArray,
+                    RecordedAllocationType::kOpData,
+                };
+
+                tflite::RecordedAllocation recorded =
+                    allocator->GetRecordedAllocation(types[allocation_type]);
+                *requested_bytes = recorded.requested_bytes;
+                *used_bytes = recorded.used_bytes;
+                *count = recorded.count;
+            });
+        }
+
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    #[test]
+    fn arena_stats() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_depthwise_conv_2d()
+            .add_fully_connected()
+            .add_softmax()
+            .add_reshape();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 12 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let interpreter = MicroInterpreter::new_recording(
+            model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+
+        let stats = interpreter.arena_stats().unwrap();
+        assert_eq!(stats.used_bytes, interpreter.arena_used_bytes());
+        assert!(stats.eval_tensor_data.count > 0);
+        assert!(stats.node_and_registration_array.used_bytes > 0);
+        assert!(stats.non_persistent_bytes > 0);
+        assert!(stats.non_persistent_bytes < stats.used_bytes);
+        assert!(
+            stats.eval_tensor_data.used_bytes
+                >= stats.eval_tensor_data.requested_bytes
+        );
+    }
+
+    #[test]
+    fn arena_stats_not_recording() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_int8.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let interpreter =
+            MicroInterpreter::new(model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        assert_eq!(interpreter.arena_stats(), None);
+    }
+}
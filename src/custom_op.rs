@@ -0,0 +1,585 @@
+//! Custom operators implemented in Rust
+//!
+//! Implement the [`CustomOp`](crate::CustomOp) trait, and register it with
+//! [`MutableOpResolver::add_custom`](crate::MutableOpResolver::add_custom)
+//! under the name used for the operator in the model.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use core::ffi::CStr;
+//! use tfmicro::{CustomOp, MutableOpResolver, NodeTensors, Status};
+//!
+//! struct Identity;
+//!
+//! impl CustomOp for Identity {
+//!     fn init(_options: &[u8]) -> Self {
+//!         Identity
+//!     }
+//!
+//!     fn invoke(
+//!         &mut self,
+//!         inputs: &NodeTensors,
+//!         outputs: &mut NodeTensors,
+//!     ) -> Result<(), Status> {
+//!         let input = inputs.get(0).ok_or(Status::Error)?;
+//!         let mut output = outputs.get_mut(0).ok_or(Status::Error)?;
+//!
+//!         output
+//!             .as_data_mut::<f32>()
+//!             .copy_from_slice(input.as_data::<f32>());
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let name = CStr::from_bytes_with_nul(b"IDENTITY\0").unwrap();
+//! let op_resolver = MutableOpResolver::empty().add_custom::<Identity>(name);
+//! ```
+
+use core::convert::TryInto;
+use core::ffi::CStr;
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+use core::ops::{Deref, DerefMut};
+use core::{ptr, slice};
+
+use crate::bindings;
+use crate::micro_interpreter::arena_buffer_alignment;
+use crate::micro_op_resolver::MutableOpResolver;
+use crate::operators::Op;
+use crate::tensor::{Tensor, TensorInfo};
+use crate::Status;
+
+cpp! {{
+    #include "op_resolver.hpp"
+    #include "tensorflow/lite/micro/kernels/kernel_util.h"
+    #include "tensorflow/lite/micro/memory_helpers.h"
+
+    typedef void* (*TfMicroInitFn)(TfLiteContext*, const char*, size_t);
+    typedef TfLiteStatus (*TfMicroPrepareFn)(TfLiteContext*, TfLiteNode*);
+    typedef TfLiteStatus (*TfMicroInvokeFn)(TfLiteContext*, TfLiteNode*);
+    typedef void (*TfMicroFreeFn)(TfLiteContext*, void*);
+}}
+
+/// A custom operator implemented in Rust
+///
+/// A value of this type is created for each node in the model that uses the
+/// operator, and lives in the tensor arena until the interpreter is dropped.
+///
+/// A panic in any of these methods aborts the program, as it cannot unwind
+/// through TensorFlow.
+///
+/// The state is `Send`, as it moves between threads with the interpreter.
+pub trait CustomOp: Sized + Send {
+    /// Create the state for a node, from the custom options stored for that
+    /// node in the model
+    fn init(options: &[u8]) -> Self;
+
+    /// Check the inputs and outputs of the node. Called once, when the
+    /// interpreter allocates tensors
+    ///
+    /// The tensor data is not yet allocated, so only
+    /// [`NodeTensors::info`](crate::NodeTensors::info) is available.
+    fn prepare(
+        &mut self,
+        _inputs: &NodeTensors,
+        _outputs: &NodeTensors,
+    ) -> Result<(), Status> {
+        Ok(())
+    }
+
+    /// Transform the input tensors of the node to its output tensors
+    fn invoke(
+        &mut self,
+        inputs: &NodeTensors,
+        outputs: &mut NodeTensors,
+    ) -> Result<(), Status>;
+
+    /// Release any resources held by the state, just before it is dropped
+    fn free(&mut self) {}
+}
+
+/// The input or output tensors of a node that uses a custom operator
+pub struct NodeTensors<'a> {
+    context: *mut cty::c_void,
+    node: *mut cty::c_void,
+    outputs: bool,
+    invoking: bool,
+    _phantom: PhantomData<&'a mut ()>,
+}
+
+impl<'a> NodeTensors<'a> {
+    /// Returns the number of tensors
+    pub fn len(&self) -> usize {
+        let node = self.node;
+        let outputs = self.outputs;
+
+        unsafe {
+            cpp!([node as "TfLiteNode*", outputs as "bool"]
+                  -> usize as "size_t" {
+                return outputs ? node->outputs->size : node->inputs->size;
+            })
+        }
+    }
+
+    /// Return whether there are zero tensors
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the `n`th tensor, or `None` if there is no such
+    /// tensor. The data of the returned tensor is only valid whilst invoking
+    fn tensor(&self, n: usize) -> Option<Tensor> {
+        let context = self.context;
+        let node = self.node;
+        let outputs = self.outputs;
+
+        let mut tensor = Tensor::default();
+        let tensor_ref = &mut tensor;
+
+        let found = unsafe {
+            cpp!([context as "TfLiteContext*",
+                  node as "TfLiteNode*",
+                  outputs as "bool",
+                  n as "size_t",
+                  tensor_ref as "TfLiteTensor*"]
+                  -> bool as "bool" {
+                const TfLiteIntArray* indices =
+                    outputs ? node->outputs : node->inputs;
+                if (n >= (size_t)indices->size || indices->data[n] < 0) {
+                    return false; // Out of range, or optional tensor
+                }
+
+                const TfLiteEvalTensor* eval =
+                    context->GetEvalTensor(context, indices->data[n]);
+                if (eval == nullptr) {
+                    return false;
+                }
+
+                size_t bytes = 0;
+                if (tflite::TfLiteEvalTensorByteLength(eval, &bytes) != kTfLiteOk) {
+                    return false;
+                }
+
+                tensor_ref->type = eval->type;
+                tensor_ref->data = eval->data;
+                tensor_ref->dims = eval->dims;
+                tensor_ref->bytes = bytes;
+                return true;
+            })
+        };
+
+        if found {
+            Some(tensor)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a [`TensorInfo`](crate::tensor::TensorInfo) that describes
+    /// the `n`th tensor
+    ///
+    /// Returns `None` if there is no such tensor, or it cannot be
+    /// represented by a [`TensorInfo`](crate::tensor::TensorInfo).
+    pub fn info(&self, n: usize) -> Option<TensorInfo<'_>> {
+        let tensor = self.tensor(n)?;
+        let element_type = tensor.inner().type_.try_into().ok()?;
+
+        // The dimensions are stored in the tensor arena
+        let dims = tensor.inner().dims;
+        let dims = unsafe {
+            let dims = &*dims;
+            dims.data.as_slice(dims.size as usize)
+        };
+
+        // Evaluation tensors carry no name or quantization parameters
+        Some(TensorInfo {
+            name: None,
+            element_type,
+            dims,
+            bytes: tensor.inner().bytes,
+            quantization: None,
+            affine_quantization: None,
+        })
+    }
+
+    /// Returns the `n`th tensor
+    ///
+    /// Returns `None` if there is no such tensor, or when called from
+    /// [`CustomOp::prepare`](crate::CustomOp::prepare).
+    pub fn get(&self, n: usize) -> Option<TensorRef<'_>> {
+        if !self.invoking {
+            return None;
+        }
+
+        Some(TensorRef {
+            tensor: self.tensor(n)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the `n`th tensor for writing
+    ///
+    /// Returns `None` if there is no such tensor, or when called from
+    /// [`CustomOp::prepare`](crate::CustomOp::prepare).
+    pub fn get_mut(&mut self, n: usize) -> Option<TensorMut<'_>> {
+        if !self.invoking {
+            return None;
+        }
+
+        Some(TensorMut {
+            tensor: self.tensor(n)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// A tensor of a node that uses a custom operator
+pub struct TensorRef<'a> {
+    tensor: Tensor,
+    _phantom: PhantomData<&'a ()>,
+}
+impl Deref for TensorRef<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Tensor {
+        &self.tensor
+    }
+}
+
+/// A tensor of a node that uses a custom operator, for writing
+pub struct TensorMut<'a> {
+    tensor: Tensor,
+    _phantom: PhantomData<&'a mut ()>,
+}
+impl Deref for TensorMut<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Tensor {
+        &self.tensor
+    }
+}
+impl DerefMut for TensorMut<'_> {
+    fn deref_mut(&mut self) -> &mut Tensor {
+        &mut self.tensor
+    }
+}
+
+type InitFn = unsafe extern "C" fn(
+    *mut cty::c_void,
+    *const u8,
+    usize,
+) -> *mut cty::c_void;
+type PrepareFn = unsafe extern "C" fn(
+    *mut cty::c_void,
+    *mut cty::c_void,
+) -> bindings::TfLiteStatus;
+type InvokeFn = PrepareFn;
+type FreeFn = unsafe extern "C" fn(*mut cty::c_void, *mut cty::c_void);
+
+/// Returns the state stored for `node` by `init_trampoline`
+unsafe fn user_data<'a, T>(node: *mut cty::c_void) -> Option<&'a mut T> {
+    let user_data = cpp!([node as "TfLiteNode*"] -> *mut cty::c_void as "void*" {
+        return node->user_data;
+    });
+
+    (user_data as *mut T).as_mut()
+}
+
+unsafe extern "C" fn init_trampoline<T: CustomOp>(
+    context: *mut cty::c_void,
+    buffer: *const u8,
+    length: usize,
+) -> *mut cty::c_void {
+    let options = if buffer.is_null() {
+        &[][..]
+    } else {
+        slice::from_raw_parts(buffer, length)
+    };
+
+    // State lives in the tensor arena
+    let size = size_of::<T>().max(1);
+    let state = cpp!([context as "TfLiteContext*", size as "size_t"]
+                     -> *mut cty::c_void as "void*" {
+        return context->AllocatePersistentBuffer(context, size);
+    });
+
+    if !state.is_null() {
+        ptr::write(state as *mut T, T::init(options));
+    }
+    state
+}
+
+unsafe extern "C" fn prepare_trampoline<T: CustomOp>(
+    context: *mut cty::c_void,
+    node: *mut cty::c_void,
+) -> bindings::TfLiteStatus {
+    let state = match user_data::<T>(node) {
+        Some(state) => state,
+        None => return bindings::TfLiteStatus::kTfLiteError,
+    };
+
+    let tensors = |outputs| NodeTensors {
+        context,
+        node,
+        outputs,
+        invoking: false,
+        _phantom: PhantomData,
+    };
+
+    match state.prepare(&tensors(false), &tensors(true)) {
+        Ok(()) => bindings::TfLiteStatus::kTfLiteOk,
+        Err(status) => status.into(),
+    }
+}
+
+unsafe extern "C" fn invoke_trampoline<T: CustomOp>(
+    context: *mut cty::c_void,
+    node: *mut cty::c_void,
+) -> bindings::TfLiteStatus {
+    let state = match user_data::<T>(node) {
+        Some(state) => state,
+        None => return bindings::TfLiteStatus::kTfLiteError,
+    };
+
+    let tensors = |outputs| NodeTensors {
+        context,
+        node,
+        outputs,
+        invoking: true,
+        _phantom: PhantomData,
+    };
+
+    match state.invoke(&tensors(false), &mut tensors(true)) {
+        Ok(()) => bindings::TfLiteStatus::kTfLiteOk,
+        Err(status) => status.into(),
+    }
+}
+
+unsafe extern "C" fn free_trampoline<T: CustomOp>(
+    _context: *mut cty::c_void,
+    buffer: *mut cty::c_void,
+) {
+    if let Some(state) = (buffer as *mut T).as_mut() {
+        state.free();
+        ptr::drop_in_place(state);
+    }
+}
+
+impl<const N: usize> MutableOpResolver<N> {
+    /// Use the custom operator `T` in this op resolver, for those nodes in
+    /// the model with custom code `name`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` requires a greater alignment than persistent buffers in
+    /// the tensor arena, which are aligned to 16 bytes.
+    pub fn add_custom<T: CustomOp>(mut self, name: &'static CStr) -> Self {
+        let alignment = arena_buffer_alignment();
+        assert!(
+            align_of::<T>() <= alignment,
+            "Custom operator state cannot be aligned to more than {} bytes.",
+            alignment
+        );
+
+        self.check_can_add(Op::Custom(name));
+        let inner_ref = &mut self.inner.resolver;
+
+        let name = name.as_ptr();
+        let init: InitFn = init_trampoline::<T>;
+        let prepare: PrepareFn = prepare_trampoline::<T>;
+        let invoke: InvokeFn = invoke_trampoline::<T>;
+        let free: FreeFn = free_trampoline::<T>;
+
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*",
+                                 name as "const char*",
+                                 init as "TfMicroInitFn",
+                                 prepare as "TfMicroPrepareFn",
+                                 invoke as "TfMicroInvokeFn",
+                                 free as "TfMicroFreeFn"] -> bool as "bool" {
+            // The registration is copied by the resolver, but `name` is not
+            TFLMRegistration registration =
+                tflite::micro::RegisterOp(init, prepare, invoke, free);
+            return inner_ref->AddCustom(name, &registration);
+        });
+
+        self.registered(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_interpreter::MicroInterpreter;
+    use crate::model::Model;
+
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Identity;
+
+    impl CustomOp for Identity {
+        fn init(_options: &[u8]) -> Self {
+            Identity
+        }
+
+        fn invoke(
+            &mut self,
+            inputs: &NodeTensors,
+            outputs: &mut NodeTensors,
+        ) -> Result<(), Status> {
+            let input = inputs.get(0).ok_or(Status::Error)?;
+            let mut output = outputs.get_mut(0).ok_or(Status::Error)?;
+
+            output
+                .as_data_mut::<f32>()
+                .copy_from_slice(input.as_data::<f32>());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn add_custom() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let name = CStr::from_bytes_with_nul(b"IDENTITY\0").unwrap();
+        let op_resolver = MutableOpResolver::empty()
+            .add_fully_connected()
+            .add_custom::<Identity>(name);
+
+        assert_eq!(op_resolver.len(), 2);
+    }
+
+    /// Nodes of `Scale` that have been freed
+    static SCALES_FREED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Multiplies by the first byte of its options
+    struct Scale {
+        factor: f32,
+        prepared: bool,
+    }
+
+    impl CustomOp for Scale {
+        fn init(options: &[u8]) -> Self {
+            Scale {
+                factor: options.first().copied().unwrap_or(1).into(),
+                prepared: false,
+            }
+        }
+
+        fn prepare(
+            &mut self,
+            inputs: &NodeTensors,
+            outputs: &NodeTensors,
+        ) -> Result<(), Status> {
+            // Only the shapes are known
+            let input = inputs.info(0).ok_or(Status::Error)?;
+            let output = outputs.info(0).ok_or(Status::Error)?;
+            if input.dims != output.dims || inputs.get(0).is_some() {
+                return Err(Status::Error);
+            }
+
+            self.prepared = true;
+            Ok(())
+        }
+
+        fn invoke(
+            &mut self,
+            inputs: &NodeTensors,
+            outputs: &mut NodeTensors,
+        ) -> Result<(), Status> {
+            if !self.prepared || inputs.len() != 1 || outputs.len() != 1 {
+                return Err(Status::Error);
+            }
+            let input = inputs.get(0).ok_or(Status::Error)?;
+            let mut output = outputs.get_mut(0).ok_or(Status::Error)?;
+
+            let input = input.as_data::<f32>();
+            for (y, x) in output.as_data_mut::<f32>().iter_mut().zip(input) {
+                *y = x * self.factor;
+            }
+            Ok(())
+        }
+
+        fn free(&mut self) {
+            SCALES_FREED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Fails to prepare
+    struct Unpreparable;
+
+    impl CustomOp for Unpreparable {
+        fn init(_options: &[u8]) -> Self {
+            Unpreparable
+        }
+
+        fn prepare(
+            &mut self,
+            _inputs: &NodeTensors,
+            _outputs: &NodeTensors,
+        ) -> Result<(), Status> {
+            Err(Status::Error)
+        }
+
+        fn invoke(
+            &mut self,
+            _inputs: &NodeTensors,
+            _outputs: &mut NodeTensors,
+        ) -> Result<(), Status> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn required_arena_size_prepare_error() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let model = include_bytes!("../tests/models/custom_scale.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        let name = CStr::from_bytes_with_nul(b"SCALE\0").unwrap();
+        let op_resolver =
+            MutableOpResolver::empty().add_custom::<Unpreparable>(name);
+
+        // Fails straight away, rather than after trying every arena size
+        match MicroInterpreter::required_arena_size(model, op_resolver) {
+            Err(crate::Error::AllocateTensorsError {
+                needed_bytes: None,
+                ..
+            }) => {}
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invoke_custom() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model, with one SCALE node whose options are [3]
+        let model = include_bytes!("../tests/models/custom_scale.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let name = CStr::from_bytes_with_nul(b"SCALE\0").unwrap();
+        let op_resolver = MutableOpResolver::empty().add_custom::<Scale>(name);
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        interpreter.input(0, &[1.0f32, 2.0, -3.0, 0.5]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(
+            interpreter.output(0).unwrap().as_data::<f32>(),
+            [3.0, 6.0, -9.0, 1.5]
+        );
+
+        drop(interpreter);
+        assert_eq!(SCALES_FREED.load(Ordering::Relaxed), 1);
+    }
+}
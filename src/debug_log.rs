@@ -0,0 +1,413 @@
+//! Messages from TensorFlow micro
+//!
+//! TensorFlow micro prints its messages with `MicroPrintf`, which ends up in
+//! `DebugLog`. This module implements `DebugLog`, so that each message is
+//! formatted and passed to the [`log`](https://crates.io/crates/log) crate
+//! with the target `tfmicro::tflm`. With the `defmt` feature, messages are
+//! passed to [`defmt`](https://crates.io/crates/defmt) instead.
+//!
+//! Messages are logged at the `Error` level by default, since TensorFlow
+//! micro mostly prints when something fails. Use [`set_tflm_log_level`] to
+//! change this.
+//!
+//! The last message is also captured, so that it can be attached to an
+//! [`Error`](crate::Error).
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{fmt, str};
+
+use log::Level;
+
+/// Target of the log records for messages from TensorFlow micro
+pub const TFLM_LOG_TARGET: &str = "tfmicro::tflm";
+
+/// Level of the log records for messages from TensorFlow micro
+static TFLM_LOG_LEVEL: AtomicUsize = AtomicUsize::new(Level::Error as usize);
+
+/// Set the level at which messages from TensorFlow micro are logged
+pub fn set_tflm_log_level(level: Level) {
+    TFLM_LOG_LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+fn tflm_log_level() -> Level {
+    match TFLM_LOG_LEVEL.load(Ordering::Relaxed) {
+        1 => Level::Error,
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+cpp! {{
+    #include <stdarg.h>
+    #include <stdio.h>
+
+    #include "tensorflow/lite/micro/debug_log.h"
+
+    // Replaces tensorflow/lite/micro/debug_log.cc, which prints to stderr
+    extern "C" void DebugLog(const char* format, va_list args) {
+    #ifndef TF_LITE_STRIP_ERROR_STRINGS
+        char buffer[256];
+        int len = vsnprintf(buffer, sizeof(buffer), format, args);
+        if (len < 0) {
+            return;
+        }
+
+        // Truncated to the buffer, without its terminator
+        size_t size = static_cast<size_t>(len);
+        if (size >= sizeof(buffer)) {
+            size = sizeof(buffer) - 1;
+        }
+
+        const char* message = buffer;
+        rust!(tfmicro_debug_log [message : *const u8 as "const char*",
+                                 size : usize as "size_t"] {
+            let message = core::slice::from_raw_parts(message, size);
+            log_message(message);
+        });
+    #endif
+    }
+
+    #ifndef TF_LITE_STRIP_ERROR_STRINGS
+    extern "C" int DebugVsnprintf(char* buffer, size_t buf_size,
+                                  const char* format, va_list vlist) {
+        return vsnprintf(buffer, buf_size, format, vlist);
+    }
+    #endif
+}}
+
+/// Log one message from TensorFlow micro
+fn log_message(message: &[u8]) {
+    // `MicroPrintf` ends each message with a separate "\r\n"
+    let message = match str::from_utf8(message) {
+        Ok(message) => message,
+        Err(e) => str::from_utf8(&message[..e.valid_up_to()]).unwrap(),
+    };
+    let message = message.trim_end_matches(&['\r', '\n'][..]);
+    if message.is_empty() {
+        return;
+    }
+
+    let mut captured = storage::load();
+    captured.record(message);
+    storage::store(&captured);
+
+    #[cfg(not(feature = "defmt"))]
+    log::log!(target: TFLM_LOG_TARGET, tflm_log_level(), "{}", message);
+
+    #[cfg(feature = "defmt")]
+    match tflm_log_level() {
+        Level::Error => defmt::error!("{=str}", message),
+        Level::Warn => defmt::warn!("{=str}", message),
+        Level::Info => defmt::info!("{=str}", message),
+        Level::Debug => defmt::debug!("{=str}", message),
+        Level::Trace => defmt::trace!("{=str}", message),
+    }
+}
+
+/// Maximum length of an [`ErrorMessage`](crate::ErrorMessage)
+const ERROR_MESSAGE_MAX_LEN: usize = 128;
+
+/// The last message reported by TensorFlow micro, see
+/// [`last_error_message`]
+///
+/// Messages are truncated to 128 bytes, so that they need no allocation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ErrorMessage {
+    message: [u8; ERROR_MESSAGE_MAX_LEN],
+    len: u8,
+}
+impl ErrorMessage {
+    /// The message as a string slice
+    pub fn as_str(&self) -> &str {
+        // Always truncated on a char boundary
+        str::from_utf8(&self.message[..self.len as usize]).unwrap()
+    }
+
+    /// Returns `true` if TensorFlow micro reported nothing
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+impl Default for ErrorMessage {
+    fn default() -> Self {
+        Self {
+            message: [0; ERROR_MESSAGE_MAX_LEN],
+            len: 0,
+        }
+    }
+}
+impl From<&str> for ErrorMessage {
+    fn from(message: &str) -> Self {
+        let mut len = message.len().min(ERROR_MESSAGE_MAX_LEN);
+        while !message.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut error_message = Self::default();
+        error_message.message[..len]
+            .copy_from_slice(&message.as_bytes()[..len]);
+        error_message.len = len as u8;
+        error_message
+    }
+}
+impl fmt::Debug for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What TensorFlow micro reported since [`clear_captured`]
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Captured {
+    /// The last message
+    pub message: ErrorMessage,
+    /// Bytes needed in the tensor arena, from the first message that
+    /// reported it
+    pub needed_bytes: Option<usize>,
+    /// Bytes that were available in the tensor arena, as `needed_bytes`
+    pub available_bytes: Option<usize>,
+}
+impl Captured {
+    fn record(&mut self, message: &str) {
+        self.message = message.into();
+
+        if self.needed_bytes.is_none() {
+            if let Some((needed, available)) = arena_shortfall(message) {
+                self.needed_bytes = Some(needed);
+                self.available_bytes = Some(available);
+            }
+        }
+    }
+}
+
+/// Parse the needed and available bytes from a message reporting that the
+/// tensor arena is too small
+fn arena_shortfall(message: &str) -> Option<(usize, usize)> {
+    // From the memory planner: "Arena size is too small for all buffers.
+    // Needed %u but only %u was available."
+    if let Some(needed) = number_after(message, "Needed ") {
+        let available = number_after(message, "but only ")?;
+        return Some((needed, available));
+    }
+
+    // From the arena allocator: "Failed to allocate tail memory. Requested:
+    // %u, available %u, missing: %u"
+    let needed = number_after(message, "Requested: ")?;
+    let available = number_after(message, "available ")?;
+    Some((needed, available))
+}
+
+/// Parse the decimal number directly after `prefix` in `message`
+fn number_after(message: &str, prefix: &str) -> Option<usize> {
+    let start = message.find(prefix)? + prefix.len();
+    let digits = &message[start..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| digits.len());
+
+    digits[..end].parse().ok()
+}
+
+/// Forget what TensorFlow micro reported so far, before calling into it
+pub(crate) fn clear_captured() {
+    storage::store(&Captured::default());
+}
+
+/// What TensorFlow micro reported since [`clear_captured`]
+pub(crate) fn captured() -> Captured {
+    storage::load()
+}
+
+/// Returns the last message reported by TensorFlow micro, to explain an
+/// [`Error`](crate::Error)
+///
+/// Messages are kept since the last interpreter was created. With the `std`
+/// feature they are kept per thread, otherwise for the whole program, so
+/// call this straight after the error.
+pub fn last_error_message() -> ErrorMessage {
+    captured().message
+}
+
+// Tests use the per thread storage, as they run in parallel, but also
+// build the atomic storage to test it directly
+#[cfg(not(any(feature = "std", test)))]
+use self::atomic_storage as storage;
+#[cfg(any(feature = "std", test))]
+use self::thread_local_storage as storage;
+
+/// Messages are captured per thread
+#[cfg(any(feature = "std", test))]
+mod thread_local_storage {
+    extern crate std;
+
+    use super::Captured;
+    use std::cell::Cell;
+
+    std::thread_local! {
+        static CAPTURED: Cell<Captured> = Cell::new(Captured::default());
+    }
+
+    pub(super) fn load() -> Captured {
+        CAPTURED.with(Cell::get)
+    }
+
+    pub(super) fn store(captured: &Captured) {
+        CAPTURED.with(|c| c.set(*captured))
+    }
+}
+
+/// Without threads, messages are captured in atomics. These only need loads
+/// and stores, which every target supports. A message could be torn if
+/// TensorFlow micro is called from an interrupt, but never invalid.
+#[cfg(any(not(feature = "std"), test))]
+mod atomic_storage {
+    use super::{Captured, ErrorMessage, ERROR_MESSAGE_MAX_LEN};
+    use core::str;
+    use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU8 = AtomicU8::new(0);
+    static MESSAGE: [AtomicU8; ERROR_MESSAGE_MAX_LEN] =
+        [ZERO; ERROR_MESSAGE_MAX_LEN];
+    static LEN: AtomicUsize = AtomicUsize::new(0);
+
+    // `usize::MAX` when not reported
+    static NEEDED_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+    static AVAILABLE_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+    pub(super) fn load() -> Captured {
+        let mut message = [0; ERROR_MESSAGE_MAX_LEN];
+        for (byte, atomic) in message.iter_mut().zip(MESSAGE.iter()) {
+            *byte = atomic.load(Ordering::Relaxed);
+        }
+        let len = LEN.load(Ordering::Relaxed).min(ERROR_MESSAGE_MAX_LEN);
+        let message = match str::from_utf8(&message[..len]) {
+            Ok(message) => message,
+            Err(e) => str::from_utf8(&message[..e.valid_up_to()]).unwrap(),
+        };
+
+        let bytes = |atomic: &AtomicUsize| match atomic.load(Ordering::Relaxed)
+        {
+            usize::MAX => None,
+            bytes => Some(bytes),
+        };
+
+        Captured {
+            message: ErrorMessage::from(message),
+            needed_bytes: bytes(&NEEDED_BYTES),
+            available_bytes: bytes(&AVAILABLE_BYTES),
+        }
+    }
+
+    pub(super) fn store(captured: &Captured) {
+        let message = captured.message.as_str().as_bytes();
+        for (byte, atomic) in message.iter().zip(MESSAGE.iter()) {
+            atomic.store(*byte, Ordering::Relaxed);
+        }
+        LEN.store(message.len(), Ordering::Relaxed);
+
+        let bytes = captured.needed_bytes.unwrap_or(usize::MAX);
+        NEEDED_BYTES.store(bytes, Ordering::Relaxed);
+        let bytes = captured.available_bytes.unwrap_or(usize::MAX);
+        AVAILABLE_BYTES.store(bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tflm_log_level_roundtrip() {
+        for &level in &[
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            set_tflm_log_level(level);
+            assert_eq!(tflm_log_level(), level);
+        }
+        set_tflm_log_level(Level::Error);
+    }
+
+    #[test]
+    fn log_message_invalid_utf8() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // Logs "Didn't find op", then nothing for the line ending
+        log_message(b"Didn't find op\xff\xfe");
+        log_message(b"\r\n");
+    }
+
+    #[test]
+    fn captured() {
+        clear_captured();
+        assert!(super::captured().message.is_empty());
+
+        log_message(b"Failed to allocate tail memory. Requested: 2048, available 1024, missing: 1024\r\n");
+        log_message(b"Failed starting model allocation.");
+        log_message(b"\r\n");
+
+        let captured = super::captured();
+        assert_eq!(
+            captured.message.as_str(),
+            "Failed starting model allocation."
+        );
+        assert_eq!(captured.needed_bytes, Some(2048));
+        assert_eq!(captured.available_bytes, Some(1024));
+    }
+
+    #[test]
+    fn arena_shortfall_planner() {
+        let message = "Arena size is too small for all buffers. Needed 8448 but only 4096 was available.";
+        assert_eq!(arena_shortfall(message), Some((8448, 4096)));
+        assert_eq!(arena_shortfall("Didn't find op for builtin opcode"), None);
+    }
+
+    #[test]
+    fn atomic_storage() {
+        let captured = atomic_storage::load();
+        assert!(captured.message.is_empty());
+        assert_eq!(captured.needed_bytes, None);
+
+        let mut captured = Captured::default();
+        captured.record("Failed to allocate tail memory. Requested: 2048, available 1024, missing: 1024");
+        atomic_storage::store(&captured);
+
+        let loaded = atomic_storage::load();
+        assert_eq!(loaded.message, captured.message);
+        assert_eq!(loaded.needed_bytes, Some(2048));
+        assert_eq!(loaded.available_bytes, Some(1024));
+
+        // Truncated to a char boundary, as for `ErrorMessage`
+        let message = "é".repeat(ERROR_MESSAGE_MAX_LEN);
+        captured.record(&message);
+        atomic_storage::store(&captured);
+
+        let loaded = atomic_storage::load();
+        assert_eq!(loaded.message.as_str().len(), ERROR_MESSAGE_MAX_LEN);
+        assert!(message.starts_with(loaded.message.as_str()));
+        assert_eq!(loaded.needed_bytes, Some(2048));
+
+        atomic_storage::store(&Captured::default());
+        assert!(atomic_storage::load().message.is_empty());
+        assert_eq!(atomic_storage::load().available_bytes, None);
+    }
+
+    #[test]
+    fn error_message_truncated() {
+        let message = "é".repeat(ERROR_MESSAGE_MAX_LEN);
+        let error_message = ErrorMessage::from(message.as_str());
+
+        assert_eq!(error_message.as_str().len(), ERROR_MESSAGE_MAX_LEN);
+    }
+}
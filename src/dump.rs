@@ -0,0 +1,320 @@
+//! Inspecting every tensor of a model, layer by layer
+//!
+//! Create an interpreter with
+//! [`MicroInterpreter::new_preserving_all_tensors`](crate::MicroInterpreter::new_preserving_all_tensors),
+//! so that each tensor keeps its own space in the tensor arena. Then
+//! [`invoke_with_node_callback`](crate::MicroInterpreter::invoke_with_node_callback)
+//! reports each operator as it runs, with its output tensors:
+//!
+//! ```ignore
+//! interpreter.invoke_with_node_callback(|node| {
+//!     for (index, tensor) in node.outputs() {
+//!         info!("{} {} tensor {}: {:?}", node.index(), node.op_name(),
+//!               index, tensor.info().dims);
+//!     }
+//! })?;
+//! ```
+//!
+//! On hosts with the `std` feature,
+//! [`write_npy_dir`](crate::MicroInterpreter::write_npy_dir) writes every
+//! tensor to a `.npy` file after an invoke, for comparison with the float
+//! model in numpy.
+
+use crate::bindings;
+use crate::micro_interpreter::MicroInterpreter;
+use crate::model::Model;
+use crate::profiler::Profiler;
+use crate::tensor::Tensor;
+use crate::Error;
+
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path};
+
+/// An operator that has just been run, see
+/// [`invoke_with_node_callback`](crate::MicroInterpreter::invoke_with_node_callback)
+pub struct Node<'n> {
+    model: &'n Model,
+    tensors: *const *mut bindings::TfLiteTensor,
+    index: usize,
+    op_name: &'static str,
+}
+
+impl<'n> Node<'n> {
+    /// Index of this node in the main subgraph
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Name of the operator, for example `"FULLY_CONNECTED"` or the name of
+    /// a custom operator
+    pub fn op_name(&self) -> &'static str {
+        self.op_name
+    }
+
+    /// Returns an iterator over the output tensors of this node, with their
+    /// indices in the main subgraph
+    pub fn outputs(&self) -> impl Iterator<Item = (usize, &Tensor)> + '_ {
+        let tensors_len = self.model.tensors_len();
+
+        (0..self.model.node_outputs_len(self.index))
+            .filter_map(move |n| self.model.node_output(self.index, n))
+            .filter(move |&index| index < tensors_len)
+            .map(move |index| {
+                let tensor = unsafe { *self.tensors.add(index) };
+                (index, tensor.into())
+            })
+    }
+}
+
+/// Calls `callback` as each profiling event ends, which is after its
+/// operator has run
+struct NodeCallback<'m, F> {
+    model: &'m Model,
+    tensors: *const *mut bindings::TfLiteTensor,
+    op_name: &'static str,
+    callback: F,
+}
+
+impl<F: FnMut(&Node<'_>)> Profiler for NodeCallback<'_, F> {
+    fn begin_event(&mut self, tag: &'static str, node: usize) -> u32 {
+        // Operators don't nest within a subgraph
+        self.op_name = tag;
+        node as u32
+    }
+
+    fn end_event(&mut self, handle: u32) {
+        let node = Node {
+            model: self.model,
+            tensors: self.tensors,
+            index: handle as usize,
+            op_name: self.op_name,
+        };
+        (self.callback)(&node)
+    }
+}
+
+impl<'a> MicroInterpreter<'a> {
+    /// Runs the model as [`invoke`](Self::invoke), calling `callback` after
+    /// each operator has run
+    ///
+    /// Nodes are numbered by the order they run in, so models with control
+    /// flow operators such as `IF` or `WHILE`, which run other subgraphs,
+    /// are not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TensorsNotPreserved` if this interpreter was not
+    /// created with
+    /// [`new_preserving_all_tensors`](Self::new_preserving_all_tensors).
+    ///
+    /// Returns `Error::InvokeError` with a TensorFlow
+    /// [`Status`](crate::Status) if an error occours in TensorFlow.
+    pub fn invoke_with_node_callback<F: FnMut(&Node<'_>)>(
+        &mut self,
+        callback: F,
+    ) -> Result<(), Error> {
+        if self.tensors.is_null() {
+            return Err(Error::TensorsNotPreserved);
+        }
+
+        let mut node_callback = NodeCallback {
+            model: self.model,
+            tensors: self.tensors,
+            op_name: "",
+            callback,
+        };
+        self.invoke_profiled(&mut node_callback)
+    }
+
+    /// Writes every tensor of the main subgraph to a `.npy` file in `dir`
+    ///
+    /// Call this after an invoke. The files are named by the index of the
+    /// tensor and its name in the model, for example
+    /// `0007_sequential_dense_1_MatMul.npy`. Quantized tensors are written
+    /// as they are stored, see [`Tensor::write_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing a file fails, or if the interpreter was
+    /// not created with
+    /// [`new_preserving_all_tensors`](Self::new_preserving_all_tensors).
+    #[cfg(feature = "std")]
+    pub fn write_npy_dir(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for index in 0..self.model.tensors_len() {
+            let tensor = self
+                .tensor(index)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            let name = self.model.tensor_name(index).unwrap_or("");
+
+            let path = dir.join(npy_file_name(index, name));
+            let file = io::BufWriter::new(fs::File::create(path)?);
+            tensor.write_npy(file)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The file name for the `index`th tensor, with any characters of `name`
+/// that are awkward in a path replaced
+#[cfg(feature = "std")]
+fn npy_file_name(index: usize, name: &str) -> String {
+    // Names of fused tensors list every op, and may be very long
+    const MAX_NAME_LEN: usize = 64;
+
+    let name: String = name
+        .chars()
+        .take(MAX_NAME_LEN)
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+
+    if name.is_empty() {
+        format!("{:04}.npy", index)
+    } else {
+        format!("{:04}_{}.npy", index, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    #[test]
+    fn node_callback() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 8 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter = MicroInterpreter::new_preserving_all_tensors(
+            &model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+
+        // Three fully connected layers, in order
+        let mut nodes = 0;
+        let mut last_output = None;
+        interpreter
+            .invoke_with_node_callback(|node| {
+                assert_eq!(node.index(), nodes);
+                assert_eq!(node.op_name(), "FULLY_CONNECTED");
+                nodes += 1;
+
+                let mut outputs = node.outputs();
+                let (index, tensor) = outputs.next().unwrap();
+                assert!(outputs.next().is_none());
+                last_output = Some((index, tensor.as_data::<f32>()[0]));
+            })
+            .unwrap();
+        assert_eq!(nodes, 3);
+
+        // The output of the last node is the output of the model
+        let (index, y) = last_output.unwrap();
+        assert_eq!(Some(index), model.io_tensor(true, 0));
+        assert_eq!(interpreter.output(0).unwrap().as_data::<f32>(), [y]);
+    }
+
+    #[test]
+    fn node_callback_not_preserved() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        let mut nodes = 0;
+        assert_eq!(
+            interpreter.invoke_with_node_callback(|_| nodes += 1),
+            Err(Error::TensorsNotPreserved)
+        );
+        assert_eq!(nodes, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_npy_dir() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        assert_eq!(
+            npy_file_name(3, "dense/MatMul;bias"),
+            "0003_dense_MatMul_bias.npy"
+        );
+        assert_eq!(npy_file_name(12, ""), "0012.npy");
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 8 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter = MicroInterpreter::new_preserving_all_tensors(
+            &model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+
+        // A directory for this test and process, removed even on failure
+        struct TempDir(std::path::PathBuf);
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = fs::remove_dir_all(&self.0);
+            }
+        }
+        let dir = TempDir(
+            std::env::temp_dir()
+                .join(format!("tfmicro_write_npy_dir_{}", std::process::id())),
+        );
+
+        interpreter.write_npy_dir(&dir.0).unwrap();
+        let files = fs::read_dir(&dir.0).unwrap().count();
+        assert_eq!(files, model.tensors_len());
+    }
+}
@@ -66,6 +66,8 @@ impl Frontend {
         if result == 1 {
             Ok(Self(state))
         } else {
+            // Frees anything allocated before the failure
+            drop(Self(state));
             Err(())
         }
     }
@@ -112,3 +114,33 @@ impl Frontend {
         num_samples_read
     }
 }
+
+impl Drop for Frontend {
+    fn drop(&mut self) {
+        let state_ref = &mut self.0;
+
+        // Frees the buffers allocated by `FrontendPopulateState`
+        cpp!(unsafe [state_ref as "FrontendState*"] {
+            FrontendFreeStateContents(state_ref);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontend_drop() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // Each frontend allocates a few KiB, which is freed on drop. Run
+        // under valgrind to check for leaks, see DEVELOP.md
+        for _ in 0..1000 {
+            let mut frontend = Frontend::new().unwrap();
+
+            let mut output = [0u16; 40];
+            frontend.generate_micro_features(&[0i16; 480], &mut output);
+        }
+    }
+}
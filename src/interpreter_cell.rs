@@ -0,0 +1,147 @@
+//! Static storage for an interpreter, for use in firmware
+//!
+//! The C++ interpreter is constructed in place in the tensor arena, and
+//! never moves. So with a `'static` model and tensor arena, a
+//! [`MicroInterpreter`] can be kept in a `static` and used from anywhere,
+//! such as an interrupt handler:
+//!
+//! ```ignore
+//! static INTERPRETER: InterpreterCell = InterpreterCell::new();
+//!
+//! let tensor_arena = cortex_m::singleton!(: [u8; 10 * 1024] = [0; 10 * 1024])
+//!     .unwrap();
+//! let interpreter: &'static mut MicroInterpreter<'static> =
+//!     INTERPRETER.init(model, op_resolver, &mut tensor_arena[..])?;
+//! ```
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::micro_interpreter::MicroInterpreter;
+use crate::micro_op_resolver::OpResolver;
+use crate::model::Model;
+use crate::Error;
+
+/// A cell holding a [`MicroInterpreter`], which can be initialised once
+///
+/// Only available on targets with atomic compare and swap, which excludes
+/// `thumbv6m-none-eabi` (Cortex-M0 and M0+, as in the stm32f0 example).
+pub struct InterpreterCell {
+    initialised: AtomicBool,
+    interpreter: UnsafeCell<MaybeUninit<MicroInterpreter<'static>>>,
+}
+
+// SAFETY: The interpreter is only handed out once, by `init`, and is `Send`
+unsafe impl Sync for InterpreterCell {}
+
+impl InterpreterCell {
+    /// Create a new, empty, cell
+    pub const fn new() -> Self {
+        Self {
+            initialised: AtomicBool::new(false),
+            interpreter: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Create an interpreter in this cell, as for
+    /// [`MicroInterpreter::new`](crate::MicroInterpreter::new), and return
+    /// a unique reference to it
+    ///
+    /// The interpreter is never dropped.
+    ///
+    /// # Errors
+    ///
+    /// As for [`MicroInterpreter::new`](crate::MicroInterpreter::new).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` has already been called on this cell, even if it
+    /// returned an error.
+    pub fn init<R: OpResolver>(
+        &'static self,
+        model: &'static Model,
+        resolver: R,
+        tensor_arena: &'static mut [u8],
+    ) -> Result<&'static mut MicroInterpreter<'static>, Error> {
+        assert!(
+            !self.initialised.swap(true, Ordering::AcqRel),
+            "This InterpreterCell has already been initialised."
+        );
+
+        let interpreter = MicroInterpreter::new(model, resolver, tensor_arena)?;
+
+        // Only reached once, so there are no other references
+        let slot = unsafe { &mut *self.interpreter.get() };
+        Ok(slot.write(interpreter))
+    }
+}
+
+impl Default for InterpreterCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    extern crate std;
+    use std::boxed::Box;
+    use std::vec;
+
+    fn init(
+        cell: &'static InterpreterCell,
+    ) -> &'static mut MicroInterpreter<'static> {
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty().add_fully_connected();
+
+        // arena
+        let tensor_arena = Box::leak(vec![0u8; 4 * 1024].into_boxed_slice());
+
+        cell.init(model, op_resolver, tensor_arena).unwrap()
+    }
+
+    #[test]
+    fn static_interpreter() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        static INTERPRETER: InterpreterCell = InterpreterCell::new();
+        let interpreter = init(&INTERPRETER);
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(interpreter.output(0).unwrap().as_data::<f32>().len(), 1);
+    }
+
+    #[test]
+    fn interpreter_from_thread() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        static INTERPRETER: InterpreterCell = InterpreterCell::new();
+        let interpreter = init(&INTERPRETER);
+
+        std::thread::spawn(move || {
+            interpreter.input(0, &[0.5f32]).unwrap();
+            interpreter.invoke().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "already been initialised")]
+    fn init_twice() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        static INTERPRETER: InterpreterCell = InterpreterCell::new();
+        init(&INTERPRETER);
+        init(&INTERPRETER);
+    }
+}
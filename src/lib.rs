@@ -75,13 +75,16 @@
 //! much the same way as in the C API. Note that unlike the C API no
 //! `error_reporter` is required. Error reports from TensorFlow are always
 //! passed to the standard Rust [`log`](https://crates.io/crates/log) framework
-//! at the `info` log level. This allows any compatible log implementation to be
-//! used.
+//! with the target `tfmicro::tflm`, at the level set by
+//! [`set_tflm_log_level`](crate::set_tflm_log_level). This allows any
+//! compatible log implementation to be used, or `defmt` with the `defmt`
+//! feature.
 //!
 //! A op_resolver is required for the interpreter. The simplest option is to
 //! pass an [`AllOpResolver`](crate::AllOpResolver), but to save memory use a
 //! [`MutableOpResolver`](crate::MutableOpResolver) with the required operations
-//! only.
+//! only. The [`op_resolver_for!`](crate::op_resolver_for) macro builds such a
+//! resolver from the `.tflite` file at compile time.
 //!
 //! ```
 //! # use tfmicro::{Model, MicroInterpreter, AllOpResolver};
@@ -119,12 +122,20 @@
 //! #     MicroInterpreter::new(&model, op_resolver, &mut arena[..]).unwrap();
 //! interpreter.invoke().unwrap();
 //!
-//! dbg!(interpreter.output(0).as_data::<f32>());
+//! dbg!(interpreter.output(0).unwrap().as_data::<f32>());
 //! ```
 //!
 //! And that's it for a minimal use case! See the [Tests](tests/) folder
 //! for more advanced use cases.
 //!
+//! An interpreter can be kept in a `static` with
+//! [`InterpreterCell`](crate::InterpreterCell), and used from an interrupt
+//! handler. This needs atomic compare and swap, so `InterpreterCell` is not
+//! available on targets without it, such as `thumbv6m-none-eabi` (Cortex-M0
+//! and M0+) used by the [stm32f0 example](examples/stm32f0). There, keep the
+//! interpreter in a `cortex_m::singleton!` or a static guarded by a critical
+//! section instead.
+//!
 //! # Developing
 //!
 //! See [DEVELOP.md](DEVELOP.md)
@@ -143,10 +154,22 @@
 #[macro_use]
 extern crate cpp;
 
+// Allows macros that expand to `::tfmicro` paths to be used in this crate
+extern crate self as tfmicro;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::fmt;
+
 mod bindings;
 mod interop;
 
 /// Error type for tfmicro
+///
+/// Errors are `Copy` and need no allocation, so the operator names they
+/// carry are truncated. The last message reported by TensorFlow micro is
+/// kept out of the error, see [`last_error_message`].
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Error {
     /// The model failed verification checks
@@ -154,14 +177,111 @@ pub enum Error {
     /// An error occoured when instantiating the interpreter
     InterpreterInitError,
     /// An error occoured when allocating tensors in the tensor arena
-    AllocateTensorsError,
+    AllocateTensorsError {
+        /// Bytes needed in the tensor arena, if TensorFlow micro reported it
+        needed_bytes: Option<usize>,
+        /// Bytes that were available in the tensor arena, if TensorFlow micro
+        /// reported it
+        available_bytes: Option<usize>,
+    },
+    /// There is no tensor with the given index
+    InvalidTensorIndex,
+    /// There is no tensor with the given name
+    TensorNotFound,
+    /// Intermediate tensors are not preserved by this interpreter
+    TensorsNotPreserved,
+    /// This interpreter was not created with
+    /// [`new_profiled`](MicroInterpreter::new_profiled)
+    NotProfiled,
+    /// An error occoured in TensorFlow while invoking a profiled
+    /// interpreter
+    InvokeError(Status),
+    /// There is no resource variable with the given handle
+    InvalidResourceVariable,
     /// The length of the supplied slice was different to expect
     InputDataLenMismatch,
     /// The element type of the underlying data is not implemented by this crate
     ElementTypeUnimplemented,
+    /// The tensor has integer elements, but no quantization parameters
+    TensorNotQuantized,
     /// An error occoured converting some raw string to UTF8
     Utf8Error,
+    /// The model uses an operator that has no binding in this crate
+    UnsupportedOperator(OpName),
+    /// The model uses operators that are missing from the op resolver
+    UnresolvedOperators(UnresolvedOperators),
 }
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidModel => f.write_str("the model failed verification"),
+            Error::InterpreterInitError => {
+                f.write_str("failed to create the interpreter")
+            }
+            Error::AllocateTensorsError {
+                needed_bytes,
+                available_bytes,
+            } => {
+                f.write_str("failed to allocate tensors")?;
+                if let (Some(needed), Some(available)) =
+                    (needed_bytes, available_bytes)
+                {
+                    write!(
+                        f,
+                        ", the tensor arena needs {} bytes but only {} are \
+                         available",
+                        needed, available
+                    )?;
+                }
+                Ok(())
+            }
+            Error::InvalidTensorIndex => {
+                f.write_str("there is no tensor with this index")
+            }
+            Error::TensorNotFound => {
+                f.write_str("there is no tensor with this name")
+            }
+            Error::TensorsNotPreserved => {
+                f.write_str("the interpreter does not preserve all tensors")
+            }
+            Error::NotProfiled => {
+                f.write_str("the interpreter was not created for profiling")
+            }
+            Error::InvokeError(status) => {
+                write!(f, "invoke failed with status {:?}", status)
+            }
+            Error::InvalidResourceVariable => {
+                f.write_str("there is no resource variable with this handle")
+            }
+            Error::InputDataLenMismatch => {
+                f.write_str("the input data has the wrong length")
+            }
+            Error::ElementTypeUnimplemented => {
+                f.write_str("the tensor element type is not implemented")
+            }
+            Error::TensorNotQuantized => {
+                f.write_str("the tensor is not quantized")
+            }
+            Error::Utf8Error => f.write_str("a string is not valid UTF-8"),
+            Error::UnsupportedOperator(name) => {
+                write!(f, "the operator {} is not supported", name)
+            }
+            Error::UnresolvedOperators(unresolved) => {
+                f.write_str("operators missing from the op resolver:")?;
+                for op in unresolved.iter() {
+                    write!(f, " {}", op.name)?;
+                }
+                let more = unresolved.len() - unresolved.iter().count();
+                if more > 0 {
+                    write!(f, " and {} more", more)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
 
 /// The status resulting from a TensorFlow operation
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -199,17 +319,100 @@ impl From<bindings::TfLiteStatus> for Status {
         }
     }
 }
+impl From<Status> for bindings::TfLiteStatus {
+    fn from(status: Status) -> Self {
+        use bindings::TfLiteStatus::*;
+
+        match status {
+            Status::Ok => kTfLiteOk,
+            Status::Error => kTfLiteError,
+            Status::DelegateError => kTfLiteDelegateError,
+            Status::ApplicationError => kTfLiteApplicationError,
+            Status::DelegateDataNotFound => kTfLiteDelegateDataNotFound,
+            Status::DelegateDataWriteError => kTfLiteDelegateDataWriteError,
+            Status::DelegateDataReadError => kTfLiteDelegateDataReadError,
+            Status::UnresolvedOps => kTfLiteUnresolvedOps,
+            Status::Cancelled => kTfLiteCancelled,
+        }
+    }
+}
 
 mod operators;
 
+mod arena_stats;
+mod custom_op;
+mod debug_log;
+mod dump;
 mod frontend;
+#[cfg(target_has_atomic = "8")]
+mod interpreter_cell;
 mod micro_interpreter;
 mod micro_op_resolver;
 mod model;
+mod profiler;
+mod signature;
 mod tensor;
+mod variables;
 
+pub use arena_stats::{ArenaStats, RecordedAllocation};
+pub use custom_op::{CustomOp, NodeTensors, TensorMut, TensorRef};
+pub use debug_log::{
+    last_error_message, set_tflm_log_level, ErrorMessage, TFLM_LOG_TARGET,
+};
+pub use dump::Node;
 pub use frontend::Frontend;
+#[cfg(target_has_atomic = "8")]
+pub use interpreter_cell::InterpreterCell;
 pub use micro_interpreter::MicroInterpreter;
-pub use micro_op_resolver::MutableOpResolver;
+pub use micro_op_resolver::{
+    AllOpResolver, MutableOpResolver, OpName, OpResolver, UnresolvedOperator,
+    UnresolvedOperators,
+};
 pub use model::Model;
+pub use operators::Op;
+#[cfg(feature = "std")]
+pub use profiler::InstantTicks;
+pub use profiler::{OpProfiler, ProfileEvent, Profiler, TickSource};
+pub use signature::{Signature, SignatureTensor};
 pub use tensor::*;
+pub use variables::ResourceVariable;
+
+/// Expands to a [`MutableOpResolver`](crate::MutableOpResolver) containing
+/// exactly the operators used by a `.tflite` model
+///
+/// The model is parsed at compile time, so only the kernels it needs are
+/// linked into the binary and the resolver cannot drift out of date when the
+/// model is retrained. The path is relative to the `Cargo.toml` of the crate
+/// invoking the macro.
+///
+/// ```ignore
+/// let op_resolver = tfmicro::op_resolver_for!("models/micro_speech.tflite");
+/// ```
+///
+/// Compilation fails if the model uses an operator that has no binding in
+/// this crate, or that requires the `cpp-std` feature when it is disabled.
+pub use tfmicro_macros::op_resolver_for;
+
+/// Used by [`op_resolver_for!`] for operators that need the `cpp-std`
+/// feature, which only this crate can check
+#[cfg(feature = "cpp-std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cpp_std_operator {
+    ($name:literal, $add:expr) => {
+        $add
+    };
+}
+#[cfg(not(feature = "cpp-std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cpp_std_operator {
+    ($name:literal, $add:expr) => {
+        compile_error!(concat!(
+            "The ",
+            $name,
+            " operator used by this model requires the cpp-std feature of \
+             tfmicro"
+        ))
+    };
+}
@@ -56,13 +56,15 @@
 //! }; // Error [model, ..] dropped here whilst still borrowed
 //!
 //! // interpreter used here
-//! interpreter.input_info(0);
+//! interpreter.input_info(0).unwrap();
 //! ```
 
 use core::convert::TryInto;
 use core::marker::PhantomData;
+use core::ptr;
 
-use crate::micro_op_resolver::MutableOpResolver;
+use crate::debug_log;
+use crate::micro_op_resolver::{sealed::Sealed, OpResolver};
 use crate::tensor::{ElemTypeOf, Tensor, TensorInfo};
 use crate::Error;
 use crate::{model::Model, Status};
@@ -70,23 +72,82 @@ use crate::{model::Model, Status};
 use crate::bindings;
 use crate::bindings::tflite;
 
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec};
+
 cpp! {{
+    #include "tensorflow/lite/micro/micro_arena_constants.h"
     #include "tensorflow/lite/micro/micro_interpreter.h"
-    #include "tensorflow/lite/micro/micro_mutable_op_resolver.h"
+    #include "tensorflow/lite/micro/micro_resource_variable.h"
+    #include "tensorflow/lite/micro/recording_micro_allocator.h"
+    #include "op_resolver.hpp"
+    #include "profiler.hpp"
     #include "tensorflow/lite/micro/kernels/micro_ops.h"
     #include "tensorflow/lite/micro/testing/micro_test.h"
     #include "tensorflow/lite/schema/schema_generated.h"
 }}
 
 /// An interpreter for TensorFlow models
+///
+/// # Placement
+///
+/// The C++ interpreter is constructed in place in the tensor arena, which
+/// stays mutably borrowed for the lifetime `'a`. So it never moves after
+/// construction, and this type is only a handle to it that may be moved
+/// freely, for example into a `static` with an
+/// [`InterpreterCell`](crate::InterpreterCell).
 pub struct MicroInterpreter<'a> {
-    // bindgen types
-    micro_interpreter: tflite::MicroInterpreter,
+    // `tflite::MicroInterpreter`, constructed in place in the tensor arena
+    pub(crate) micro_interpreter: *mut tflite::MicroInterpreter,
+
+    // The model, for the names of its tensors
+    pub(crate) model: &'a Model,
+
+    // `tflite::RecordingMicroAllocator`, if allocations are recorded
+    pub(crate) recording_allocator: *const cty::c_void,
+
+    // `TfMicroProfiler` in the tensor arena, if operators are profiled
+    pub(crate) profiler: *mut cty::c_void,
+
+    // Every tensor keeps its own space in the arena, see `tensor`
+    preserve_all_tensors: bool,
+
+    // Table in the tensor arena of a `TfLiteTensor` for each tensor in the
+    // main subgraph, if all tensors are preserved
+    pub(crate) tensors: *const *mut bindings::TfLiteTensor,
+
+    // `tflite::MicroResourceVariables` in the tensor arena, if the model
+    // has resource variables
+    pub(crate) resource_variables: *mut cty::c_void,
+
+    // Arena passed to `new_owned`, from `Box::into_raw`
+    #[cfg(feature = "alloc")]
+    owned_arena: Option<*mut [u8]>,
 
     // See https://doc.rust-lang.org/std/marker/struct.PhantomData.html#unused-lifetime-parameters
     _phantom: PhantomData<&'a ()>,
 }
 
+// SAFETY: The raw pointers are to the C++ interpreter and its allocations in
+// the tensor arena, which the interpreter owns through the borrow of the
+// arena. TensorFlow micro keeps no thread-local state, and the only Rust
+// state in the arena is that of custom operators, which are `Send`. So the
+// interpreter may be used from another thread. It is not `Sync`, as the C++
+// interpreter is not safe to call from two threads at once.
+unsafe impl Send for MicroInterpreter<'_> {}
+
+/// Options for creating the underlying C++ interpreter
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Options {
+    /// Record allocations in the tensor arena
+    pub(crate) recording: bool,
+    /// Install a profiler, which may be attached during an invoke
+    pub(crate) profiling: bool,
+    /// Plan the arena so that no tensors share memory. Not compatible with
+    /// `recording`
+    pub(crate) preserve_all_tensors: bool,
+}
+
 impl<'a> MicroInterpreter<'a> {
     // From tensorflow source:
     // tensorflow/lite/micro/micro_interpreter.h
@@ -103,136 +164,533 @@ impl<'a> MicroInterpreter<'a> {
     /// Create a new micro_interpreter from a Model, a MicroOpResolver and a
     /// tensor arena (scratchpad).
     ///
+    /// The resolver may be a [`MutableOpResolver`](crate::MutableOpResolver)
+    /// of any capacity or an [`AllOpResolver`](crate::AllOpResolver).
+    ///
+    /// The C++ interpreter is constructed in place in the tensor arena,
+    /// along with the registrations of those operators used by the model, so
+    /// `resolver` is not needed after this returns. The interpreter never
+    /// moves, and is destroyed when this is dropped.
+    ///
     /// # Errors
     ///
+    /// Returns `Error::UnresolvedOperators` if the model uses operators that
+    /// are missing from `resolver`.
+    ///
     /// Returns `Error::InterpreterInitError` if there is an error creating
     /// the interpreter.
     ///
-    /// Returns `Error::AllocateTensors` if there is error in the call to
-    /// `AllocateTensors`.
-    pub fn new<'m: 'a, 't: 'a>(
+    /// Returns `Error::AllocateTensorsError` if there is error in the call to
+    /// `AllocateTensors`. If the tensor arena is too small, this reports the
+    /// bytes needed and available.
+    pub fn new<'m: 'a, 't: 'a, R: OpResolver>(
         model: &'m Model,
-        resolver: MutableOpResolver,
+        resolver: R,
         tensor_arena: &'a mut [u8],
     ) -> Result<Self, Error> {
-        let resolver = resolver.to_inner();
+        Self::create(model, &resolver, tensor_arena, Options::default())
+    }
+
+    /// Create a new micro_interpreter that preserves every tensor in the
+    /// model, so that intermediate tensors can be read with
+    /// [`tensor`](Self::tensor) after an invoke, or after each operator with
+    /// [`invoke_with_node_callback`](Self::invoke_with_node_callback)
+    ///
+    /// Normally tensors whose lifetimes don't overlap share space in the
+    /// tensor arena. Here each has its own, so the arena needs to be much
+    /// larger than for [`new`](Self::new). This is intended for debugging.
+    ///
+    /// # Errors
+    ///
+    /// As for [`new`](Self::new).
+    pub fn new_preserving_all_tensors<'m: 'a, R: OpResolver>(
+        model: &'m Model,
+        resolver: R,
+        tensor_arena: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        Self::create(
+            model,
+            &resolver,
+            tensor_arena,
+            Options {
+                profiling: true,
+                preserve_all_tensors: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Create a new micro_interpreter that owns its tensor arena, for
+    /// example a `Vec<u8>` or `Box<[u8]>`
+    ///
+    /// Only the model is borrowed, so the interpreter can be stored in a
+    /// struct alongside it. The arena is on the heap, so it does not move
+    /// when the interpreter does, and it is freed when the interpreter is
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// As for [`new`](Self::new).
+    #[cfg(feature = "alloc")]
+    pub fn new_owned<'m: 'a, R: OpResolver, A: Into<Box<[u8]>>>(
+        model: &'m Model,
+        resolver: R,
+        tensor_arena: A,
+    ) -> Result<Self, Error> {
+        // Ownership is held through a raw pointer, as the C++ interpreter
+        // keeps a pointer into the arena
+        let tensor_arena = Box::into_raw(tensor_arena.into());
+        let interpreter = Self::create(
+            model,
+            &resolver,
+            unsafe { &mut *tensor_arena },
+            Options::default(),
+        );
+
+        match interpreter {
+            Ok(mut interpreter) => {
+                interpreter.owned_arena = Some(tensor_arena);
+                Ok(interpreter)
+            }
+            Err(e) => {
+                drop(unsafe { Box::from_raw(tensor_arena) });
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the size of tensor arena needed to run `model` with
+    /// `resolver`
+    ///
+    /// The interpreter is created in a scratch arena, which is grown by the
+    /// shortfall that TensorFlow micro reports until creation succeeds, and
+    /// the size used is read back with
+    /// [`arena_used_bytes`](Self::arena_used_bytes). Padding is added for
+    /// aligning the start of the arena, so an arena of the returned size
+    /// works wherever it is placed.
+    ///
+    /// The size depends on the pointer width of the target, so the result
+    /// from a 64-bit host is an upper bound for a 32-bit microcontroller.
+    /// A build script can use this to generate a constant for firmware:
+    ///
+    /// ```ignore
+    /// // build.rs, with tfmicro as a build dependency
+    /// let model = Model::from_buffer(&MODEL[..]).unwrap();
+    /// let size =
+    ///     MicroInterpreter::required_arena_size(model, AllOpResolver::new())
+    ///         .unwrap();
+    ///
+    /// let out = Path::new(&env::var("OUT_DIR").unwrap()).join("arena.rs");
+    /// fs::write(out, format!("const TENSOR_ARENA_SIZE: usize = {};", size))
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnresolvedOperators` if the model uses operators that
+    /// are missing from `resolver`.
+    ///
+    /// Returns `Error::InterpreterInitError` or
+    /// `Error::AllocateTensorsError` if the interpreter can't be created for
+    /// any reason other than the scratch arena being too small, or needs
+    /// more than 16 MiB.
+    #[cfg(feature = "alloc")]
+    pub fn required_arena_size<R: OpResolver>(
+        model: &Model,
+        resolver: R,
+    ) -> Result<usize, Error> {
+        // Scratch arena sizes to try, in bytes
+        const SCRATCH_ARENA_MIN: usize = 16 * 1024;
+        const SCRATCH_ARENA_MAX: usize = 16 * 1024 * 1024;
+
+        let mut size = SCRATCH_ARENA_MIN;
+        loop {
+            let mut scratch = vec![0u8; size];
+
+            match MicroInterpreter::create(
+                model,
+                &resolver,
+                &mut scratch,
+                Options::default(),
+            ) {
+                Ok(interpreter) => {
+                    return Ok(interpreter.arena_used_bytes()
+                        + arena_buffer_alignment());
+                }
+                // Only retry when this attempt reported how much the arena
+                // is short by. Other errors won't go away
+                Err(Error::AllocateTensorsError {
+                    needed_bytes: Some(needed),
+                    available_bytes,
+                    ..
+                }) if size < SCRATCH_ARENA_MAX => {
+                    let shortfall =
+                        needed.saturating_sub(available_bytes.unwrap_or(0));
+                    size = (size + shortfall + arena_buffer_alignment())
+                        .min(SCRATCH_ARENA_MAX);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Create the underlying C++ interpreter, and allocate its tensors
+    pub(crate) fn create<R: OpResolver>(
+        model: &'a Model,
+        resolver: &R,
+        tensor_arena: &mut [u8],
+        options: Options,
+    ) -> Result<Self, Error> {
+        let Options {
+            recording,
+            profiling,
+            preserve_all_tensors,
+        } = options;
+        debug_assert!(!(recording && preserve_all_tensors));
+        resolver.check_resolves(model)?;
+        let resolver_ref = resolver.inner_ref();
 
         let tensor_arena_size = tensor_arena.len();
         let tensor_arena = tensor_arena.as_mut_ptr();
+        let tensors_len = model.tensors_len();
+        let var_handles_len = model.var_handles_len();
 
         let mut init_status = bindings::TfLiteStatus::kTfLiteError;
         let mut allocate_status = bindings::TfLiteStatus::kTfLiteError;
+        let mut recording_allocator: *const cty::c_void = ptr::null();
+        let mut profiler: *mut cty::c_void = ptr::null_mut();
+        let mut tensors: *const *mut bindings::TfLiteTensor = ptr::null();
+        let mut resource_variables: *mut cty::c_void = ptr::null_mut();
+
+        // Capture the messages reported when creating the interpreter
+        debug_log::clear_captured();
 
         // Create interpreter
-        let mut micro_interpreter = unsafe {
+        let micro_interpreter = unsafe {
             let init_status_ref = &mut init_status;
             let allocate_status_ref = &mut allocate_status;
+            let recording_allocator_ref = &mut recording_allocator;
+            let profiler_ref = &mut profiler;
+            let tensors_ref = &mut tensors;
+            let resource_variables_ref = &mut resource_variables;
 
             cpp! ([
                 model as "const tflite::Model*",
-                resolver as "tflite::MicroMutableOpResolver<128>",
+                resolver_ref as "const TfMicroOpResolver*",
                 tensor_arena as "uint8_t*",
                 tensor_arena_size as "size_t",
+                recording as "bool",
+                profiling as "bool",
+                preserve_all_tensors as "bool",
+                tensors_len as "size_t",
+                var_handles_len as "size_t",
                 init_status_ref as "TfLiteStatus*",
-                allocate_status_ref as "TfLiteStatus*"
-            ] -> tflite::MicroInterpreter as "tflite::MicroInterpreter"
+                allocate_status_ref as "TfLiteStatus*",
+                recording_allocator_ref as "const void**",
+                profiler_ref as "TfMicroProfiler**",
+                tensors_ref as "TfLiteTensor***",
+                resource_variables_ref as "tflite::MicroResourceVariables**"
+            ] -> *mut tflite::MicroInterpreter as "tflite::MicroInterpreter*"
               {
-                  tflite::MicroInterpreter interpreter(model,
-                                                       resolver,
-                                                       tensor_arena,
-                                                       tensor_arena_size);
+                  // The allocator is placed in the tensor arena, as the
+                  // MicroInterpreter constructor taking an arena does
+                  tflite::MicroAllocator* allocator;
+                  if (recording) {
+                      tflite::RecordingMicroAllocator* recording_allocator =
+                          tflite::RecordingMicroAllocator::Create(
+                              tensor_arena, tensor_arena_size);
+                      *recording_allocator_ref = recording_allocator;
+                      allocator = recording_allocator;
+                  } else {
+                      // The linear planner gives every tensor its own
+                      // space
+                      allocator = tflite::MicroAllocator::Create(
+                          tensor_arena,
+                          tensor_arena_size,
+                          preserve_all_tensors
+                              ? tflite::MemoryPlannerType::kLinear
+                              : tflite::MemoryPlannerType::kGreedy);
+                  }
+                  if (allocator == nullptr) {
+                      return nullptr;
+                  }
+
+                  // Also in the arena, so it doesn't move with the
+                  // interpreter
+                  if (profiling) {
+                      void* buffer = allocator->AllocatePersistentBuffer(
+                          sizeof(TfMicroProfiler));
+                      if (buffer == nullptr) {
+                          return nullptr;
+                      }
+                      *profiler_ref = new (buffer) TfMicroProfiler();
+                  }
+
+                  // The interpreter keeps pointers to the registrations in
+                  // the resolver, so those used by the model are copied
+                  // into the arena. The Rust resolver may then be dropped
+                  void* resolver_buffer = allocator->AllocatePersistentBuffer(
+                      TfMicroOpResolver::SizeFor(model));
+                  if (resolver_buffer == nullptr) {
+                      return nullptr;
+                  }
+                  const TfMicroOpResolver* resolver =
+                      resolver_ref->CopyFor(model, resolver_buffer);
+
+                  // VAR_HANDLE operators need somewhere to keep resource
+                  // variables. Each has at most one
+                  if (var_handles_len > 0) {
+                      *resource_variables_ref =
+                          tflite::MicroResourceVariables::Create(
+                              allocator, var_handles_len);
+                      if (*resource_variables_ref == nullptr) {
+                          return nullptr;
+                      }
+                  }
+
+                  // The interpreter is constructed in place in the arena,
+                  // as it holds pointers to itself. It never moves, and is
+                  // destroyed when the Rust interpreter is dropped
+                  void* buffer = allocator->AllocatePersistentBuffer(
+                      sizeof(tflite::MicroInterpreter));
+                  if (buffer == nullptr) {
+                      return nullptr;
+                  }
+                  tflite::MicroInterpreter* interpreter =
+                      new (buffer) tflite::MicroInterpreter(model,
+                                                            *resolver,
+                                                            allocator,
+                                                            *resource_variables_ref,
+                                                            *profiler_ref);
 
                   // Get status
-                  *init_status_ref = interpreter.initialization_status();
+                  *init_status_ref = interpreter->initialization_status();
                   if (*init_status_ref != kTfLiteOk) {
+                    interpreter->~MicroInterpreter();
+                    return nullptr;
+                  }
+
+                  *allocate_status_ref = interpreter->AllocateTensors();
+                  if (*allocate_status_ref != kTfLiteOk) {
+                    interpreter->~MicroInterpreter();
+                    return nullptr;
+                  }
+                  if (!preserve_all_tensors || tensors_len == 0) {
                     return interpreter;
                   }
 
-                  *allocate_status_ref = interpreter.AllocateTensors();
+                  // GetTensor places a new TfLiteTensor in the arena on
+                  // each call, so each tensor is only fetched once
+                  TfLiteTensor** tensors =
+                      static_cast<TfLiteTensor**>(
+                          allocator->AllocatePersistentBuffer(
+                              sizeof(TfLiteTensor*) * tensors_len));
+                  for (size_t i = 0; tensors != nullptr && i < tensors_len;
+                       i++) {
+                    tensors[i] = interpreter->GetTensor(i);
+                    if (tensors[i] == nullptr) {
+                      tensors = nullptr;
+                    }
+                  }
+                  if (tensors == nullptr) {
+                    *allocate_status_ref = kTfLiteError;
+                    interpreter->~MicroInterpreter();
+                    return nullptr;
+                  }
+                  *tensors_ref = tensors;
 
                   return interpreter;
               })
         };
 
+        // The interpreter is null, and already destroyed, on any error
         if init_status != bindings::TfLiteStatus::kTfLiteOk {
             return Err(Error::InterpreterInitError);
         }
         if allocate_status != bindings::TfLiteStatus::kTfLiteOk {
-            return Err(Error::AllocateTensorsError);
+            let captured = debug_log::captured();
+            return Err(Error::AllocateTensorsError {
+                needed_bytes: captured.needed_bytes,
+                available_bytes: captured.available_bytes,
+            });
         }
+        assert!(!micro_interpreter.is_null());
 
-        // Create self
         Ok(Self {
             micro_interpreter,
+            model,
+            recording_allocator,
+            profiler,
+            preserve_all_tensors,
+            tensors,
+            resource_variables,
+            #[cfg(feature = "alloc")]
+            owned_arena: None,
             _phantom: PhantomData,
         })
     }
 
-    /// Returns a [`TensorInfo`](crate::tensor::TensorInfo) that describes
-    /// the `n`th input tensor.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the underlying tensor cannot be represented by a
-    /// [`TensorInfo`](crate::tensor::TensorInfo).
-    pub fn input_info(&self, n: usize) -> TensorInfo {
-        let interpreter = &self.micro_interpreter;
-        let input_tensor: &'a Tensor = unsafe {
+    /// Returns the number of input tensors
+    pub fn inputs_size(&self) -> usize {
+        let interpreter = self.micro_interpreter;
+        unsafe {
+            cpp!([interpreter as "const tflite::MicroInterpreter*"]
+                  -> usize as "size_t" {
+                return interpreter->inputs_size();
+            })
+        }
+    }
+
+    /// Returns the number of output tensors
+    pub fn outputs_size(&self) -> usize {
+        let interpreter = self.micro_interpreter;
+        unsafe {
+            cpp!([interpreter as "const tflite::MicroInterpreter*"]
+                  -> usize as "size_t" {
+                return interpreter->outputs_size();
+            })
+        }
+    }
+
+    /// Returns a pointer to the `n`th input tensor, or
+    /// `Err(Error::InvalidTensorIndex)` if there is no such tensor
+    fn input_ptr(
+        &self,
+        n: usize,
+    ) -> Result<*mut bindings::TfLiteTensor, Error> {
+        if n >= self.inputs_size() {
+            return Err(Error::InvalidTensorIndex);
+        }
+
+        let interpreter = self.micro_interpreter;
+        unsafe {
             // Call method on micro_interpreter
             let inp = cpp!([
                 interpreter as "tflite::MicroInterpreter*",
                 n as "size_t"]
                 -> *mut bindings::TfLiteTensor as "TfLiteTensor*" {
-
                 return interpreter->input(n);
             });
 
             // Check result
             assert!(!inp.is_null(), "Obtained nullptr from TensorFlow");
+            Ok(inp)
+        }
+    }
 
-            // From bindgen type to Rust type
-            inp.into()
-        };
+    /// Returns a pointer to the `n`th output tensor, or
+    /// `Err(Error::InvalidTensorIndex)` if there is no such tensor
+    fn output_ptr(
+        &self,
+        n: usize,
+    ) -> Result<*mut bindings::TfLiteTensor, Error> {
+        if n >= self.outputs_size() {
+            return Err(Error::InvalidTensorIndex);
+        }
+
+        let interpreter = self.micro_interpreter;
+        unsafe {
+            // Call method on micro_interpreter
+            let out = cpp!([
+                interpreter as "tflite::MicroInterpreter*",
+                n as "size_t"]
+                -> *mut bindings::TfLiteTensor as "TfLiteTensor*" {
+                return interpreter->output(n);
+            });
+
+            // Check result
+            assert!(!out.is_null(), "Obtained nullptr from TensorFlow");
+            Ok(out)
+        }
+    }
+
+    /// Returns a [`TensorInfo`](crate::tensor::TensorInfo) that describes
+    /// the `n`th input tensor, including its name in the model
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th input
+    /// tensor.
+    ///
+    /// Returns `Error::ElementTypeUnimplemented` if the tensor cannot be
+    /// represented by a [`TensorInfo`](crate::tensor::TensorInfo).
+    pub fn input_info(&self, n: usize) -> Result<TensorInfo, Error> {
+        let input_tensor: &Tensor = self.input_ptr(n)?.into();
+        let mut info: TensorInfo = input_tensor.inner().try_into()?;
+        info.name = self
+            .model
+            .io_tensor(false, n)
+            .and_then(|tensor| self.model.tensor_name(tensor));
 
-        // Panics if tensor cannot be repesented (eg. unimplemented type)
-        input_tensor.info()
+        Ok(info)
+    }
+
+    /// Returns a [`TensorInfo`](crate::tensor::TensorInfo) that describes
+    /// the `n`th output tensor, including its name in the model
+    ///
+    /// # Errors
+    ///
+    /// As for [`input_info`](Self::input_info).
+    pub fn output_info(&self, n: usize) -> Result<TensorInfo, Error> {
+        let output_tensor: &Tensor = self.output_ptr(n)?.into();
+        let mut info: TensorInfo = output_tensor.inner().try_into()?;
+        info.name = self
+            .model
+            .io_tensor(true, n)
+            .and_then(|tensor| self.model.tensor_name(tensor));
+
+        Ok(info)
+    }
+
+    /// Returns an iterator over the descriptions of the input tensors, in
+    /// order
+    ///
+    /// ```
+    /// # use tfmicro::{Model, MicroInterpreter, AllOpResolver};
+    /// # let model_array = include_bytes!("../examples/models/hello_world.tflite");
+    /// # let model = Model::from_buffer(&model_array[..]).unwrap();
+    /// # const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+    /// # let mut arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+    /// # let op_resolver = AllOpResolver::new();
+    /// # let interpreter =
+    /// #     MicroInterpreter::new(&model, op_resolver, &mut arena[..]).unwrap();
+    /// for info in interpreter.inputs() {
+    ///     let info = info.unwrap();
+    ///     println!("{:?} {:?} {:?}", info.name, info.element_type, info.dims);
+    /// }
+    /// ```
+    pub fn inputs(
+        &self,
+    ) -> impl Iterator<Item = Result<TensorInfo<'_>, Error>> + '_ {
+        (0..self.inputs_size()).map(move |n| self.input_info(n))
+    }
+
+    /// Returns an iterator over the descriptions of the output tensors, in
+    /// order
+    pub fn outputs(
+        &self,
+    ) -> impl Iterator<Item = Result<TensorInfo<'_>, Error>> + '_ {
+        (0..self.outputs_size()).map(move |n| self.output_info(n))
     }
 
     /// Clones data into the `n`th input tensor.
     ///
     /// # Errors
     ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th input
+    /// tensor.
+    ///
     /// Returns `Error::InputDataLenMismatch` if the length of slice `data`
     /// does not match the flat length of the `n`th input tensor.
     ///
     /// Returns an Error if the underlying tensor cannot be represented by a
     /// [`TensorInfo`](crate::tensor::TensorInfo).
-    ///
-    /// # Panics
-    ///
-    /// Panics if the attempt to get a pointer from TensorFlow returns a
-    /// nullptr. This can occour if the tensor index `n` is invalid.
     pub fn input<T: ElemTypeOf + core::clone::Clone>(
         &mut self,
         n: usize,
         data: &[T],
     ) -> Result<(), Error> {
-        let interpreter = &self.micro_interpreter;
-        let input_tensor: &mut Tensor = unsafe {
-            // Call method on micro_interpreter
-            let inp = cpp!([
-                interpreter as "tflite::MicroInterpreter*",
-                n as "size_t"]
-                -> *mut bindings::TfLiteTensor as "TfLiteTensor*" {
-                return interpreter->input(n);
-            });
-
-            // Check result
-            assert!(!inp.is_null(), "Obtained nullptr from TensorFlow");
-
-            // From bindgen type to Rust type
-            inp.into()
-        };
+        let input_tensor = self.input_mut(n)?;
 
         // Returns Err if tensor cannot be repesented (eg. unimplemented
         // type)
@@ -249,6 +707,66 @@ impl<'a> MicroInterpreter<'a> {
         }
     }
 
+    /// Returns a mutable reference to the `n`th input tensor
+    ///
+    /// The tensor data lives in the tensor arena, so a sensor or DMA
+    /// transfer can write into it directly, without the copy made by
+    /// [`input`](Self::input). The tensor is borrowed from the interpreter,
+    /// so it must be released before calling [`invoke`](Self::invoke).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th input
+    /// tensor.
+    pub fn input_mut(&mut self, n: usize) -> Result<&mut Tensor, Error> {
+        // From bindgen type to Rust type
+        Ok(self.input_ptr(n)?.into())
+    }
+
+    /// Returns the data of the `n`th input tensor as a mutable flat slice
+    ///
+    /// ```ignore
+    /// let image: &mut [i8] = interpreter.input_data_mut(0)?;
+    /// camera.capture_into(image);
+    /// interpreter.invoke()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th input
+    /// tensor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` does not match the element type of the tensor, as for
+    /// [`Tensor::as_data_mut`](crate::Tensor::as_data_mut).
+    pub fn input_data_mut<T: ElemTypeOf>(
+        &mut self,
+        n: usize,
+    ) -> Result<&mut [T], Error> {
+        Ok(self.input_mut(n)?.as_data_mut())
+    }
+
+    /// Quantizes `data` into the `n`th input tensor, using its scale and
+    /// zero point
+    ///
+    /// The input tensor may be Int8, UInt8 or Int16, or Float32 to copy
+    /// `data` unchanged. See
+    /// [`Tensor::quantize_from`](crate::Tensor::quantize_from).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th input
+    /// tensor.
+    ///
+    /// Otherwise as for
+    /// [`Tensor::quantize_from`](crate::Tensor::quantize_from).
+    pub fn input_f32(&mut self, n: usize, data: &[f32]) -> Result<(), Error> {
+        // From bindgen type to Rust type
+        let input_tensor: &mut Tensor = self.input_ptr(n)?.into();
+        input_tensor.quantize_from(data)
+    }
+
     /// Runs the Tensorflow operation to transform input tensors to output
     /// tensors
     ///
@@ -257,7 +775,7 @@ impl<'a> MicroInterpreter<'a> {
     /// Returns a TensorFlow [`Status`](crate::Status) if an error occours in
     /// TensorFlow.
     pub fn invoke(&mut self) -> Result<(), Status> {
-        let interpreter = &self.micro_interpreter;
+        let interpreter = self.micro_interpreter;
 
         let status = unsafe {
             cpp!([interpreter as "tflite::MicroInterpreter*"]
@@ -275,33 +793,91 @@ impl<'a> MicroInterpreter<'a> {
 
     /// Returns an immutable reference to the nth output tensor
     ///
-    /// # Panics
+    /// The tensor is stored in the tensor arena, so it is borrowed from the
+    /// interpreter. It can't be held across an invoke, which overwrites it:
     ///
-    /// Panics if the attempt to get a pointer from TensorFlow returns a
-    /// nullptr. This can occour if the tensor index `n` is invalid.
-    pub fn output(&self, n: usize) -> &'a Tensor {
-        let interpreter = &self.micro_interpreter;
-        unsafe {
-            // Call method on micro_interpreter
-            let out = cpp!([
-                interpreter as "tflite::MicroInterpreter*",
-                n as "size_t"]
-                    -> *mut bindings::TfLiteTensor as "TfLiteTensor*" {
-                return interpreter->output(n);
-            });
+    /// ```compile_fail
+    /// # use tfmicro::{Model, MicroInterpreter, AllOpResolver};
+    /// # let model_array = include_bytes!("../examples/models/hello_world.tflite");
+    /// # let model = Model::from_buffer(&model_array[..]).unwrap();
+    /// # const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+    /// # let mut arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+    /// # let op_resolver = AllOpResolver::new();
+    /// # let mut interpreter =
+    /// #     MicroInterpreter::new(&model, op_resolver, &mut arena[..]).unwrap();
+    /// let output = interpreter.output(0).unwrap();
+    /// interpreter.invoke().unwrap(); // Error, output is still borrowed
+    /// println!("{:?}", output.as_data::<f32>());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th output
+    /// tensor.
+    pub fn output(&self, n: usize) -> Result<&Tensor, Error> {
+        // From bindgen type to Rust type
+        Ok(self.output_ptr(n)?.into())
+    }
 
-            // Check result
-            assert!(!out.is_null(), "Obtained nullptr from Tensorflow!");
+    /// Returns a mutable reference to the nth output tensor, for
+    /// post-processing in place
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `n`th output
+    /// tensor.
+    pub fn output_mut(&mut self, n: usize) -> Result<&mut Tensor, Error> {
+        // From bindgen type to Rust type
+        Ok(self.output_ptr(n)?.into())
+    }
 
-            // From bindgen type to Rust type
-            out.into()
+    /// Returns an immutable reference to the `index`th tensor in the main
+    /// subgraph of the model, including intermediate tensors
+    ///
+    /// The interpreter must be created with
+    /// [`new_preserving_all_tensors`](Self::new_preserving_all_tensors).
+    /// Otherwise intermediate tensors share space in the tensor arena, and
+    /// are overwritten during an invoke.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TensorsNotPreserved` if the interpreter was not
+    /// created with
+    /// [`new_preserving_all_tensors`](Self::new_preserving_all_tensors).
+    ///
+    /// Returns `Error::InvalidTensorIndex` if there is no `index`th tensor.
+    pub fn tensor(&self, index: usize) -> Result<&Tensor, Error> {
+        if !self.preserve_all_tensors {
+            return Err(Error::TensorsNotPreserved);
         }
+        if index >= self.model.tensors_len() {
+            return Err(Error::InvalidTensorIndex);
+        }
+
+        // From bindgen type to Rust type
+        Ok(unsafe { *self.tensors.add(index) }.into())
+    }
+
+    /// Returns a [`TensorInfo`](crate::tensor::TensorInfo) that describes
+    /// the `index`th tensor in the main subgraph, including its name
+    ///
+    /// # Errors
+    ///
+    /// As for [`tensor`](Self::tensor).
+    ///
+    /// Returns `Error::ElementTypeUnimplemented` if the tensor cannot be
+    /// represented by a [`TensorInfo`](crate::tensor::TensorInfo).
+    pub fn tensor_info(&self, index: usize) -> Result<TensorInfo, Error> {
+        let mut info: TensorInfo = self.tensor(index)?.inner().try_into()?;
+        info.name = self.model.tensor_name(index);
+
+        Ok(info)
     }
 
     /// Returns the actual number of bytes required for the arena
     ///
     pub fn arena_used_bytes(&self) -> usize {
-        let interpreter = &self.micro_interpreter;
+        let interpreter = self.micro_interpreter;
         unsafe {
             cpp!([interpreter as "tflite::MicroInterpreter*"]
                   -> usize as "size_t" {
@@ -311,6 +887,34 @@ impl<'a> MicroInterpreter<'a> {
     }
 }
 
+/// Alignment of the start of the tensor arena, and of persistent buffers in
+/// it. TensorFlow micro skips any bytes before the first aligned address
+pub(crate) fn arena_buffer_alignment() -> usize {
+    unsafe {
+        cpp!([] -> usize as "size_t" {
+            return tflite::MicroArenaBufferAlignment();
+        })
+    }
+}
+
+impl Drop for MicroInterpreter<'_> {
+    fn drop(&mut self) {
+        // Frees the state of each operator. The arena itself is not touched
+        let interpreter = self.micro_interpreter;
+        unsafe {
+            cpp!([interpreter as "tflite::MicroInterpreter*"] {
+                interpreter->~MicroInterpreter();
+            })
+        };
+
+        // Only after the interpreter, which refers to it
+        #[cfg(feature = "alloc")]
+        if let Some(tensor_arena) = self.owned_arena.take() {
+            drop(unsafe { Box::from_raw(tensor_arena) });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -340,14 +944,102 @@ mod tests {
                 .unwrap();
     }
 
-    #[cfg(feature = "alloc")]
-    extern crate alloc;
+    #[test]
+    fn drop_interpreter() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        // Each interpreter is destroyed before the arena is reused. Run
+        // under valgrind to check for leaks, see DEVELOP.md
+        let mut outputs = [0.0f32; 2];
+        for n in 0..1000 {
+            // The resolver is dropped by `new`, so is not used after it
+            let op_resolver = MutableOpResolver::empty().add_fully_connected();
+            let mut interpreter = MicroInterpreter::new(
+                &model,
+                op_resolver,
+                &mut tensor_arena[..],
+            )
+            .unwrap();
+
+            interpreter.input(0, &[0.5f32]).unwrap();
+            interpreter.invoke().unwrap();
+            outputs[n.min(1)] = interpreter.output(0).unwrap().as_data()[0];
+        }
+        assert_eq!(outputs[0], outputs[1]);
+
+        // The interpreter doesn't move in memory, even when its handle does
+        let op_resolver = MutableOpResolver::empty().add_fully_connected();
+        let interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+        let mut interpreters = [None, None];
+        interpreters[1] = Some(interpreter);
+        let interpreter = interpreters[1].as_mut().unwrap();
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(
+            interpreter.output(0).unwrap().as_data::<f32>(),
+            [outputs[0]]
+        );
+    }
+
+    #[test]
+    fn quantized_input_output() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_int8.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        // sin(pi / 2) = 1
+        interpreter
+            .input_f32(0, &[core::f32::consts::FRAC_PI_2])
+            .unwrap();
+        interpreter.invoke().unwrap();
+
+        let mut y = [0.0f32];
+        interpreter
+            .output(0)
+            .unwrap()
+            .dequantize_into(&mut y)
+            .unwrap();
+        assert!(y[0] > 0.9 && y[0] < 1.1, "sin(pi / 2) = {}", y[0]);
+
+        let output = interpreter.output(0).unwrap();
+        assert_eq!(output.dequantized().unwrap().next(), Some(y[0]));
+        assert_eq!(
+            output.dequantize_into(&mut [0.0; 2]).err(),
+            Some(Error::InputDataLenMismatch)
+        );
+    }
 
     #[cfg(feature = "alloc")]
     use alloc::{vec, vec::Vec};
 
     #[test]
-    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[cfg(feature = "alloc")]
     fn new_interpreter_alloc_arena() {
         let _ = env_logger::builder().is_test(true).try_init();
 
@@ -356,13 +1048,43 @@ mod tests {
         let model = Model::from_buffer(&model[..]).unwrap();
 
         // resolver
-        let op_resolver = MutableOpResolver::empty();
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
 
         // arena
         let tensor_arena: Vec<u8> = vec![0u8; 4 * 1024];
 
-        let _ =
-            MicroInterpreter::new(&model, op_resolver, tensor_arena).unwrap();
+        let _ = MicroInterpreter::new_owned(&model, op_resolver, tensor_arena)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn required_arena_size() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        let op_resolver = || {
+            MutableOpResolver::empty()
+                .add_depthwise_conv_2d()
+                .add_fully_connected()
+                .add_softmax()
+                .add_reshape()
+        };
+
+        let size = MicroInterpreter::required_arena_size(model, op_resolver())
+            .unwrap();
+
+        let mut tensor_arena: Vec<u8> = vec![0u8; size];
+        let interpreter =
+            MicroInterpreter::new(model, op_resolver(), &mut tensor_arena[..])
+                .unwrap();
+        assert!(interpreter.arena_used_bytes() <= size);
     }
 
     #[test]
@@ -387,10 +1109,265 @@ mod tests {
             MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
                 .unwrap();
 
-        let info = interpreter.input_info(0);
+        let info = interpreter.input_info(0).unwrap();
 
         // input tensor properties for hello_world example
         assert_eq!(info.element_type, ElementType::Float32);
         assert_eq!(info.dims, [1, 1]);
+        assert_eq!(info.bytes, 4);
+        assert_eq!(info.quantization, None);
+    }
+
+    #[test]
+    fn inputs_outputs() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_int8.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        assert_eq!(interpreter.inputs_size(), 1);
+        assert_eq!(interpreter.outputs_size(), 1);
+        assert_eq!(interpreter.inputs().count(), 1);
+        assert_eq!(interpreter.outputs().count(), 1);
+
+        for info in interpreter.inputs().chain(interpreter.outputs()) {
+            let info = info.unwrap();
+            assert!(info.name.is_some());
+            assert_eq!(info.element_type, ElementType::Int8);
+            assert_eq!(info.bytes, 1);
+            assert!(info.quantization.unwrap().scale > 0.0);
+
+            // Per-tensor, so the same as `quantization`
+            let affine = info.affine_quantization.unwrap();
+            assert!(!affine.is_per_channel());
+            assert_eq!(affine.channel(0), info.quantization);
+        }
+
+        // Out of range
+        assert_eq!(
+            interpreter.input_f32(1, &[0.0]).err(),
+            Some(Error::InvalidTensorIndex)
+        );
+        assert_eq!(
+            interpreter.input_info(1).err(),
+            Some(Error::InvalidTensorIndex)
+        );
+        assert_eq!(
+            interpreter.output(1).err(),
+            Some(Error::InvalidTensorIndex)
+        );
+    }
+
+    #[test]
+    fn input_data_mut() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        // Written in place, the same as copying with `input`
+        interpreter.input_data_mut::<f32>(0).unwrap()[0] = 0.5;
+        interpreter.invoke().unwrap();
+        let in_place = interpreter.output(0).unwrap().as_data::<f32>()[0];
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+        let copied = interpreter.output(0).unwrap().as_data::<f32>()[0];
+        assert_eq!(in_place, copied);
+
+        let input = interpreter.input_mut(0).unwrap();
+        assert_eq!(input.as_data::<f32>(), [0.5]);
+        assert_eq!(
+            interpreter.input_mut(1).err(),
+            Some(Error::InvalidTensorIndex)
+        );
+    }
+
+    #[test]
+    fn output_mut() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+
+        let output = interpreter.output_mut(0).unwrap();
+        let y = output.as_data::<f32>()[0];
+        output.as_data_mut::<f32>()[0] = y * 2.0;
+        assert_eq!(interpreter.output(0).unwrap().as_data::<f32>(), [y * 2.0]);
+
+        // Intermediate tensors are only available when preserved
+        assert_eq!(
+            interpreter.tensor(0).err(),
+            Some(Error::TensorsNotPreserved)
+        );
+    }
+
+    #[test]
+    fn preserve_all_tensors() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 8 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter = MicroInterpreter::new_preserving_all_tensors(
+            &model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+
+        // The output is one of the model's tensors
+        let output_index = model.io_tensor(true, 0).unwrap();
+        let output = interpreter.output(0).unwrap().as_data::<f32>()[0];
+        let tensor = interpreter.tensor(output_index).unwrap();
+        assert_eq!(tensor.as_data::<f32>(), [output]);
+
+        // Every tensor of the main subgraph can be read
+        for index in 0..model.tensors_len() {
+            let info = interpreter.tensor_info(index).unwrap();
+            assert_eq!(info.name, model.tensor_name(index));
+        }
+        assert_eq!(
+            interpreter.tensor(model.tensors_len()).err(),
+            Some(Error::InvalidTensorIndex)
+        );
+    }
+
+    #[test]
+    fn unresolved_operators() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver, missing softmax
+        let op_resolver = MutableOpResolver::empty()
+            .add_depthwise_conv_2d()
+            .add_fully_connected()
+            .add_reshape();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 10 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let unresolved = match MicroInterpreter::new(
+            &model,
+            op_resolver,
+            &mut tensor_arena[..],
+        ) {
+            Err(Error::UnresolvedOperators(unresolved)) => unresolved,
+            other => {
+                panic!("Expected unresolved operators, got {:?}", other.err())
+            }
+        };
+
+        assert_eq!(unresolved.len(), 1);
+        let op = unresolved.iter().next().unwrap();
+        assert_eq!(op.name.as_str(), "SOFTMAX");
+        assert_eq!(op.subgraph, 0);
+    }
+
+    #[test]
+    fn arena_too_small() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_depthwise_conv_2d()
+            .add_fully_connected()
+            .add_softmax()
+            .add_reshape();
+
+        // arena, smaller than the model needs
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let error =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .err()
+                .unwrap();
+
+        match error {
+            Error::AllocateTensorsError {
+                needed_bytes: Some(needed),
+                available_bytes: Some(available),
+            } => {
+                assert!(!crate::last_error_message().is_empty());
+                assert!(needed > available);
+                assert!(available < TENSOR_ARENA_SIZE);
+            }
+            other => panic!("Expected arena too small, got {:?}", other),
+        }
+
+        extern crate std;
+        let display = std::format!("{}", error);
+        assert!(display.starts_with("failed to allocate tensors"));
     }
 }
@@ -1,50 +1,101 @@
 //! Tensorflow Lite Op Resolvers
 //!
 
-use core::fmt;
+use core::convert::TryFrom;
+use core::ffi::CStr;
+use core::{fmt, ptr, str};
+
+use crate::bindings;
+use crate::bindings::tflite::BuiltinOperator;
+use crate::model::Model;
+use crate::operators::Op;
+use crate::Error;
 
 cpp! {{
-    #include "tensorflow/lite/micro/micro_mutable_op_resolver.h"
+    #include "op_resolver.hpp"
+    #include "tensorflow/lite/schema/schema_utils.h"
 }}
 
-#[cfg(target_pointer_width = "64")]
-type InnerMutableOpResolver128 = [u64; 8728 / 8];
-
-#[cfg(target_pointer_width = "32")]
-type InnerMutableOpResolver128 = [u32; 4620 / 4];
+/// Storage for an op resolver with capacity `N`
+///
+/// The layout matches `TfMicroOpResolver` in csrc/op_resolver.hpp, which
+/// is followed by its registrations and their parsers. The sizes are checked
+/// when compiling the C++.
+#[repr(C)]
+pub(crate) struct Inner<const N: usize> {
+    pub(crate) resolver: [usize; 3],
+    _registrations: [bindings::TFLMRegistration; N],
+    _parsers: [usize; N],
+}
 
 /// An Op Resolver that has no operators by default, but can be added by
 /// calling methods in a builder pattern
-pub struct MutableOpResolver {
-    pub(crate) inner: InnerMutableOpResolver128,
-    capacity: usize,
+///
+/// The resolver has space for `N` operators, 128 by default. Storage is
+/// sized for exactly `N` operators, so choosing a smaller capacity saves
+/// memory:
+///
+/// ```
+/// # use tfmicro::MutableOpResolver;
+/// let op_resolver = MutableOpResolver::<3>::new()
+///     .add_depthwise_conv_2d()
+///     .add_fully_connected()
+///     .add_softmax();
+/// ```
+pub struct MutableOpResolver<const N: usize = 128> {
+    pub(crate) inner: Inner<N>,
     len: usize,
 }
-impl fmt::Debug for MutableOpResolver {
+impl<const N: usize> fmt::Debug for MutableOpResolver<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_args!("MutableOpResolver (ops = {})", self.len))
+        struct Ops<'r, const N: usize>(&'r MutableOpResolver<N>);
+        impl<const N: usize> fmt::Debug for Ops<'_, N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.0.ops()).finish()
+            }
+        }
+
+        f.debug_struct("MutableOpResolver")
+            .field("capacity", &N)
+            .field("ops", &Ops(self))
+            .finish()
     }
 }
-impl Default for MutableOpResolver {
+impl<const N: usize> Default for MutableOpResolver<N> {
     fn default() -> Self {
-        Self::empty()
+        Self::new()
     }
 }
 
 impl MutableOpResolver {
-    pub(crate) fn to_inner(self) -> InnerMutableOpResolver128 {
-        self.inner
+    /// Create a new MutableOpResolver with the default capacity, initially
+    /// empty
+    pub fn empty() -> Self {
+        Self::new()
     }
+}
 
-    /// Check the number of operators is OK
-    pub(crate) fn check_then_inc_len(&mut self) {
+impl<const N: usize> MutableOpResolver<N> {
+    /// Check there is space for `op`, and that it has not been added already
+    pub(crate) fn check_can_add(&self, op: Op) {
+        assert!(
+            self.len < N,
+            "This op resolver does not support more than {} operators.",
+            N
+        );
         assert!(
-            self.len < self.capacity,
-            "Tensorflow micro does not support more than {} operators.",
-            self.capacity
+            !self.contains(op),
+            "{:?} has already been added to this op resolver.",
+            op
         );
+    }
 
-        self.len += 1;
+    /// Count an operator if it was added to the underlying resolver
+    pub(crate) fn registered(mut self, added: bool) -> Self {
+        if added {
+            self.len += 1;
+        }
+        self
     }
 
     /// Returns the current number of operators in this resolver
@@ -57,28 +108,507 @@ impl MutableOpResolver {
         self.len == 0
     }
 
-    /// Create a new MutableOpResolver, initially empty
-    pub fn empty() -> Self {
-        // Maximum number of registrations
-        let tflite_registrations_max = 128;
+    /// Returns the maximum number of operators in this resolver
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Iterate over the operators in this resolver, in the order they were
+    /// added
+    pub fn ops(&self) -> impl Iterator<Item = Op> + '_ {
+        (0..self.len).map(move |n| self.op(n))
+    }
+
+    /// Returns whether `op` has been added to this resolver
+    pub fn contains(&self, op: Op) -> bool {
+        self.ops().any(|o| o == op)
+    }
+
+    /// Returns the `n`th operator in this resolver
+    fn op(&self, n: usize) -> Op {
+        let inner_ref = &self.inner.resolver;
+        let mut code: BuiltinOperator::Type = 0;
+        let mut name: *const cty::c_char = ptr::null();
+
+        unsafe {
+            let code_ref = &mut code;
+            let name_ref = &mut name;
+            cpp!([inner_ref as "const TfMicroOpResolver*",
+                  n as "size_t",
+                  code_ref as "int32_t*",
+                  name_ref as "const char**"] {
+                inner_ref->GetOp(n, code_ref, name_ref);
+            });
+        }
+
+        if code != BuiltinOperator::BuiltinOperator_CUSTOM {
+            return Op::try_from(code)
+                .expect("Only operators with bindings are added");
+        }
+
+        // Custom operators are added with a `&'static CStr` name, or a
+        // string literal in TensorFlow micro
+        let name: &'static CStr = unsafe { CStr::from_ptr(name) };
+        name.to_str()
+            .ok()
+            .and_then(Op::from_custom_name)
+            .unwrap_or(Op::Custom(name))
+    }
+
+    /// Create a new MutableOpResolver with space for `N` operators,
+    /// initially empty
+    pub fn new() -> Self {
+        let mut inner = Inner {
+            resolver: [0; 3],
+            _registrations: [Default::default(); N],
+            _parsers: [0; N],
+        };
+
+        let inner_ref = &mut inner.resolver;
+        let capacity = N;
+        unsafe {
+            cpp!([inner_ref as "void*", capacity as "size_t"] {
+                new (inner_ref) TfMicroOpResolver(capacity);
+            })
+        };
+
+        Self { inner, len: 0 }
+    }
+
+    /// Create a new MutableOpResolver with exactly those operators used by
+    /// `model`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperator` naming the first operator in the
+    /// model that has no binding in this crate.
+    ///
+    /// Returns `Error::Utf8Error` if the name of a custom operator in the
+    /// model is not valid UTF8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model uses more than `N` distinct operators.
+    pub fn for_model(model: &Model) -> Result<Self, Error> {
+        let mut resolver = Self::new();
+
+        for n in 0..model.operator_codes_len() {
+            let op = match model.custom_code(n)? {
+                Some(name) => Op::from_custom_name(name),
+                None => Op::try_from(model.builtin_code(n)).ok(),
+            };
+            let op = match op.filter(Op::is_supported) {
+                Some(op) => op,
+                None => {
+                    return Err(Error::UnsupportedOperator(
+                        model.operator_name(n)?,
+                    ))
+                }
+            };
 
-        let micro_op_resolver = unsafe {
-            cpp!([] -> InnerMutableOpResolver128 as
-                 "tflite::MicroMutableOpResolver<128>" {
+            // The operator codes table has an entry for each version of an
+            // operator, but each operator may only be registered once
+            if !resolver.contains(op) {
+                resolver = resolver.add(op);
+            }
+        }
+
+        Ok(resolver)
+    }
+
+    /// Returns whether the `n`th entry in the operator codes table of
+    /// `model` is registered in this resolver
+    fn resolves(&self, model: &Model, n: usize) -> bool {
+        let inner_ref = &self.inner.resolver;
+
+        unsafe {
+            cpp!([inner_ref as "const TfMicroOpResolver*",
+                  model as "const tflite::Model*",
+                  n as "size_t"]
+                  -> bool as "bool" {
+                auto opcode = model->operator_codes()->Get(n);
+                auto code = tflite::GetBuiltinCode(opcode);
 
-                tflite::MicroMutableOpResolver<128> resolver;
-                return resolver;
+                if (code == tflite::BuiltinOperator_CUSTOM) {
+                    return opcode->custom_code() != nullptr
+                        && inner_ref->FindOp(opcode->custom_code()->c_str()) != nullptr;
+                }
+                return inner_ref->FindOp(code) != nullptr;
             })
+        }
+    }
+
+    /// Check that every operator used by `model` is registered in this
+    /// resolver
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnresolvedOperators` with the first missing operator,
+    /// and the subgraph and node index where it was used, and the number of
+    /// operators missing.
+    ///
+    /// Returns `Error::Utf8Error` if the name of a custom operator in the
+    /// model is not valid UTF8.
+    pub(crate) fn check_resolves(&self, model: &Model) -> Result<(), Error> {
+        let mut unresolved = UnresolvedOperators::default();
+
+        for n in 0..model.operator_codes_len() {
+            if model.first_use(n).is_none() || self.resolves(model, n) {
+                continue;
+            }
+
+            // The operator codes table has an entry for each version of an
+            // operator. Report each operator once, where any version of it
+            // was first used
+            let name = model.operator_name(n)?;
+            let mut first_use: Option<(usize, usize)> = None;
+            for m in 0..model.operator_codes_len() {
+                let used = match model.first_use(m) {
+                    Some(used) => used,
+                    None => continue,
+                };
+                if model.operator_name(m)? != name {
+                    continue;
+                }
+
+                if m < n {
+                    // Already reported
+                    first_use = None;
+                    break;
+                }
+                first_use = Some(match first_use {
+                    Some(earlier) => earlier.min(used),
+                    None => used,
+                });
+            }
+
+            if let Some((subgraph, node)) = first_use {
+                unresolved.push(UnresolvedOperator {
+                    name,
+                    subgraph,
+                    node,
+                });
+            }
+        }
+
+        if unresolved.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnresolvedOperators(unresolved))
+        }
+    }
+}
+
+impl MutableOpResolver {
+    /// Create a new MutableOpResolver with every operator supported by this
+    /// crate added
+    ///
+    /// This is convenient whilst prototyping, but links every kernel into
+    /// the binary. See [`AllOpResolver`](crate::AllOpResolver).
+    pub fn all() -> Self {
+        Self::empty()
+            .add_abs()
+            .add_add()
+            .add_add_n()
+            .add_argmax()
+            .add_argmin()
+            .add_assign_variable()
+            .add_average_pool_2d()
+            .add_batch_mat_mul()
+            .add_batch_to_space_nd()
+            .add_broadcast_args()
+            .add_broadcast_to()
+            .add_call_once()
+            .add_cast()
+            .add_ceil()
+            .add_concatenation()
+            .add_conv_2d()
+            .add_cos()
+            .add_cum_sum()
+            .add_depth_to_space()
+            .add_depthwise_conv_2d()
+            .add_dequantize()
+            .add_div()
+            .add_elu()
+            .add_embedding_lookup()
+            .add_equal()
+            .add_ethos_u()
+            .add_exp()
+            .add_expand_dims()
+            .add_fill()
+            .add_floor()
+            .add_floor_div()
+            .add_floor_mod()
+            .add_fully_connected()
+            .add_gather()
+            .add_gather_nd()
+            .add_greater()
+            .add_greater_equal()
+            .add_hard_swish()
+            .add_if()
+            .add_l2_normalization()
+            .add_l2_pool_2d()
+            .add_leaky_relu()
+            .add_less()
+            .add_less_equal()
+            .add_log()
+            .add_logical_and()
+            .add_logical_not()
+            .add_logical_or()
+            .add_logistic()
+            .add_log_softmax()
+            .add_maximum()
+            .add_max_pool_2d()
+            .add_mirror_pad()
+            .add_mean()
+            .add_minimum()
+            .add_mul()
+            .add_neg()
+            .add_not_equal()
+            .add_pack()
+            .add_pad_v2()
+            .add_prelu()
+            .add_quantize()
+            .add_read_variable()
+            .add_reduce_max()
+            .add_relu()
+            .add_relu6()
+            .add_reshape()
+            .add_resize_bilinear()
+            .add_resize_nearest_neighbor()
+            .add_round()
+            .add_rsqrt()
+            .add_select_v2()
+            .add_shape()
+            .add_sin()
+            .add_slice()
+            .add_softmax()
+            .add_space_to_batch_nd()
+            .add_space_to_depth()
+            .add_split()
+            .add_split_v()
+            .add_squeeze()
+            .add_sqrt()
+            .add_square()
+            .add_squared_difference()
+            .add_strided_slice()
+            .add_sub()
+            .add_sum()
+            .add_svdf()
+            .add_tanh()
+            .add_transpose_conv()
+            .add_unpack()
+            .add_unidirectional_sequence_lstm()
+            .add_var_handle()
+            .add_while()
+            .add_zeros_like()
+            .add_cpp_std_ops()
+    }
+
+    /// Add those operators that require the C++ standard library
+    #[cfg(feature = "cpp-std")]
+    fn add_cpp_std_ops(self) -> Self {
+        self.add_circular_buffer()
+            .add_delay()
+            .add_detection_postprocess()
+            .add_energy()
+            .add_fft_auto_scale()
+            .add_filter_bank()
+            .add_filter_bank_log()
+            .add_filter_bank_spectral_subtraction()
+            .add_filter_bank_square_root()
+            .add_framer()
+            .add_irfft()
+            .add_overlap_add()
+            .add_pcan()
+            .add_rfft()
+            .add_stacker()
+            .add_window()
+    }
+
+    #[cfg(not(feature = "cpp-std"))]
+    fn add_cpp_std_ops(self) -> Self {
+        self
+    }
+}
+
+/// Maximum length of an [`OpName`](crate::OpName), in bytes. Together with
+/// its length, a name fills 40 bytes
+const OP_NAME_MAX_LEN: usize = 39;
+
+/// The name of a builtin or custom operator, as found in a model
+///
+/// Names longer than 39 bytes are truncated.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct OpName {
+    name: [u8; OP_NAME_MAX_LEN],
+    len: u8,
+}
+impl OpName {
+    /// The name as a string slice
+    pub fn as_str(&self) -> &str {
+        // Always truncated on a char boundary
+        str::from_utf8(&self.name[..self.len as usize]).unwrap()
+    }
+}
+impl From<&str> for OpName {
+    fn from(name: &str) -> Self {
+        let mut len = name.len().min(OP_NAME_MAX_LEN);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut op_name = Self {
+            name: [0; OP_NAME_MAX_LEN],
+            len: len as u8,
         };
+        op_name.name[..len].copy_from_slice(&name.as_bytes()[..len]);
+        op_name
+    }
+}
+impl fmt::Debug for OpName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl fmt::Display for OpName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An operator used by a model that is missing from the op resolver
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct UnresolvedOperator {
+    /// Name of the builtin or custom operator
+    pub name: OpName,
+    /// Index of the subgraph where the operator was first used
+    pub subgraph: usize,
+    /// Index of the node within that subgraph
+    pub node: usize,
+}
+
+/// Maximum number of operators recorded in
+/// [`UnresolvedOperators`](crate::UnresolvedOperators), which keeps
+/// [`Error`](crate::Error) small
+const UNRESOLVED_OPERATORS_MAX: usize = 1;
+
+/// The operators used by a model that are missing from the op resolver
+///
+/// Only the first operator is recorded, but [`len`](Self::len) counts them
+/// all.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct UnresolvedOperators {
+    ops: [UnresolvedOperator; UNRESOLVED_OPERATORS_MAX],
+    len: usize,
+}
+impl UnresolvedOperators {
+    fn push(&mut self, op: UnresolvedOperator) {
+        if let Some(slot) = self.ops.get_mut(self.len) {
+            *slot = op;
+        }
+        self.len += 1;
+    }
+
+    /// Returns the number of unresolved operators
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether there are zero unresolved operators
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the recorded unresolved operators
+    pub fn iter(&self) -> impl Iterator<Item = &UnresolvedOperator> {
+        self.ops[..self.len.min(UNRESOLVED_OPERATORS_MAX)].iter()
+    }
+}
+impl fmt::Debug for UnresolvedOperators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.iter());
 
-        Self {
-            inner: micro_op_resolver,
-            capacity: tflite_registrations_max,
-            len: 0,
+        if self.len > UNRESOLVED_OPERATORS_MAX {
+            let more = self.len - UNRESOLVED_OPERATORS_MAX;
+            list.entry(&format_args!("and {} more", more));
         }
+        list.finish()
     }
 }
 
+/// An Op Resolver that contains every operator supported by this crate
+///
+/// Useful for prototyping a model before trimming down to the operators it
+/// needs. To save memory use a [`MutableOpResolver`] with the required
+/// operations only.
+pub struct AllOpResolver(MutableOpResolver);
+impl fmt::Debug for AllOpResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("AllOpResolver (ops = {})", self.0.len))
+    }
+}
+impl Default for AllOpResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl From<AllOpResolver> for MutableOpResolver {
+    fn from(resolver: AllOpResolver) -> Self {
+        resolver.0
+    }
+}
+
+impl AllOpResolver {
+    /// Create a new AllOpResolver
+    pub fn new() -> Self {
+        Self(MutableOpResolver::all())
+    }
+}
+
+/// An op resolver that can be passed to
+/// [`MicroInterpreter::new`](crate::MicroInterpreter::new)
+///
+/// Implemented by [`MutableOpResolver`] of any capacity and
+/// [`AllOpResolver`]. This trait is sealed.
+pub trait OpResolver: sealed::Sealed {}
+
+pub(crate) mod sealed {
+    use super::*;
+
+    pub trait Sealed {
+        /// The underlying `TfMicroOpResolver`
+        fn inner_ref(&self) -> &[usize; 3];
+
+        /// See [`MutableOpResolver::check_resolves`]
+        fn check_resolves(&self, model: &Model) -> Result<(), Error>;
+    }
+
+    impl<const N: usize> Sealed for MutableOpResolver<N> {
+        fn inner_ref(&self) -> &[usize; 3] {
+            &self.inner.resolver
+        }
+
+        fn check_resolves(&self, model: &Model) -> Result<(), Error> {
+            MutableOpResolver::check_resolves(self, model)
+        }
+    }
+
+    impl Sealed for AllOpResolver {
+        fn inner_ref(&self) -> &[usize; 3] {
+            self.0.inner_ref()
+        }
+
+        fn check_resolves(&self, model: &Model) -> Result<(), Error> {
+            self.0.check_resolves(model)
+        }
+    }
+}
+
+impl<const N: usize> OpResolver for MutableOpResolver<N> {}
+impl OpResolver for AllOpResolver {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,4 +728,145 @@ mod tests {
                 .add_window();
         }
     }
+
+    #[test]
+    fn mutable_op_resolver_capacity() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let ops = MutableOpResolver::<2>::new().add_add().add_mul();
+
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops.capacity(), 2);
+        assert_eq!(MutableOpResolver::empty().capacity(), 128);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mutable_op_resolver_full() {
+        let _ = MutableOpResolver::<1>::new().add_add().add_mul();
+    }
+
+    #[test]
+    fn mutable_op_resolver_ops() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let ops = MutableOpResolver::empty()
+            .add_depthwise_conv_2d()
+            .add_fully_connected()
+            .add_softmax()
+            .add_reshape();
+
+        assert!(ops.ops().eq([
+            Op::DepthwiseConv2D,
+            Op::FullyConnected,
+            Op::Softmax,
+            Op::Reshape,
+        ]));
+        assert!(ops.contains(Op::Softmax));
+        assert!(!ops.contains(Op::Add));
+    }
+
+    #[test]
+    #[should_panic(expected = "Softmax has already been added")]
+    fn mutable_op_resolver_duplicate() {
+        let _ = MutableOpResolver::empty().add_softmax().add_softmax();
+    }
+
+    #[test]
+    fn mutable_op_resolver_from_ops() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let ops = [Op::DepthwiseConv2D, Op::FullyConnected, Op::Softmax];
+        let op_resolver: MutableOpResolver<3> = ops.iter().copied().collect();
+
+        assert!(op_resolver.ops().eq(ops.iter().copied()));
+    }
+
+    #[test]
+    fn op_builtin_code() {
+        use BuiltinOperator::*;
+
+        assert_eq!(Op::try_from(BuiltinOperator_SOFTMAX), Ok(Op::Softmax));
+        assert_eq!(
+            BuiltinOperator::Type::from(Op::Softmax),
+            BuiltinOperator_SOFTMAX
+        );
+        assert_eq!(
+            BuiltinOperator::Type::from(Op::Delay),
+            BuiltinOperator_CUSTOM
+        );
+        assert!(Op::try_from(BuiltinOperator_CUSTOM).is_err());
+    }
+
+    #[test]
+    fn all_op_resolver() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let ops: MutableOpResolver = AllOpResolver::new().into();
+
+        assert!(!ops.is_empty());
+        assert_eq!(ops.len(), MutableOpResolver::all().len());
+    }
+
+    #[test]
+    fn op_resolver_for_model() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        let op_resolver: MutableOpResolver =
+            MutableOpResolver::for_model(model).unwrap();
+        assert!(!op_resolver.is_empty());
+
+        const TENSOR_ARENA_SIZE: usize = 10 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let _ = crate::MicroInterpreter::new(
+            model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn op_resolver_for_macro() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let op_resolver = crate::op_resolver_for!("submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        let for_model: MutableOpResolver =
+            MutableOpResolver::for_model(model).unwrap();
+        assert_eq!(op_resolver.len(), for_model.len());
+        assert_eq!(op_resolver.capacity(), op_resolver.len());
+    }
+
+    #[test]
+    fn op_name_truncated() {
+        let name: OpName = "SignalFilterBankSpectralSubtraction".into();
+        assert_eq!(name.as_str(), "SignalFilterBankSpectralSubtraction");
+
+        let long = [b'x'; 2 * OP_NAME_MAX_LEN];
+        let name: OpName = str::from_utf8(&long).unwrap().into();
+        assert_eq!(name.as_str().len(), OP_NAME_MAX_LEN);
+    }
+
+    #[test]
+    fn unresolved_operators_recorded() {
+        let mut unresolved = UnresolvedOperators::default();
+        for node in 0..5 {
+            unresolved.push(UnresolvedOperator {
+                name: "CUSTOM".into(),
+                subgraph: 0,
+                node,
+            });
+        }
+
+        assert_eq!(unresolved.len(), 5);
+        assert!(unresolved.iter().map(|op| op.node).eq(0..1));
+    }
 }
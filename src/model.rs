@@ -1,8 +1,18 @@
 //! TensorFlow model
 
+use core::ffi::CStr;
+use core::{slice, str};
+
 use crate::bindings::tflite;
+use crate::bindings::tflite::BuiltinOperator;
+use crate::micro_op_resolver::OpName;
 use crate::Error;
 
+cpp! {{
+    #include "tensorflow/lite/schema/schema_generated.h"
+    #include "tensorflow/lite/schema/schema_utils.h"
+}}
+
 /// A TensorFlow model
 #[repr(transparent)]
 #[derive(Default)]
@@ -37,6 +47,280 @@ impl Model {
             Err(Error::InvalidModel)
         }
     }
+
+    /// Returns the number of entries in the operator codes table of this
+    /// model
+    pub(crate) fn operator_codes_len(&self) -> usize {
+        let model = self;
+        unsafe {
+            cpp!([model as "const tflite::Model*"] -> usize as "size_t" {
+                auto codes = model->operator_codes();
+                return codes == nullptr ? 0 : codes->size();
+            })
+        }
+    }
+
+    /// Returns the builtin code of the `n`th entry in the operator codes
+    /// table. Custom operators have the code `BuiltinOperator_CUSTOM`.
+    pub(crate) fn builtin_code(&self, n: usize) -> BuiltinOperator::Type {
+        assert!(n < self.operator_codes_len());
+
+        let model = self;
+        unsafe {
+            cpp!([model as "const tflite::Model*", n as "size_t"]
+                  -> BuiltinOperator::Type as "int32_t" {
+                return tflite::GetBuiltinCode(model->operator_codes()->Get(n));
+            })
+        }
+    }
+
+    /// Returns the name of the `n`th entry in the operator codes table, if
+    /// it is a custom operator
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Utf8Error` if the name is not valid UTF8
+    pub(crate) fn custom_code(&self, n: usize) -> Result<Option<&str>, Error> {
+        assert!(n < self.operator_codes_len());
+
+        let model = self;
+        let mut len = 0usize;
+        let len_ref = &mut len;
+
+        let name = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  n as "size_t",
+                  len_ref as "size_t*"]
+                  -> *const u8 as "const char*" {
+                auto opcode = model->operator_codes()->Get(n);
+                if (tflite::GetBuiltinCode(opcode) != tflite::BuiltinOperator_CUSTOM
+                    || opcode->custom_code() == nullptr) {
+                    return nullptr;
+                }
+
+                *len_ref = opcode->custom_code()->size();
+                return opcode->custom_code()->c_str();
+            })
+        };
+
+        if name.is_null() {
+            return Ok(None);
+        }
+
+        let name = unsafe { slice::from_raw_parts(name, len) };
+        str::from_utf8(name).map(Some).or(Err(Error::Utf8Error))
+    }
+
+    /// Returns the name of the `n`th entry in the operator codes table
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Utf8Error` if the name is not valid UTF8
+    pub(crate) fn operator_name(&self, n: usize) -> Result<OpName, Error> {
+        Ok(match self.custom_code(n)? {
+            Some(name) => name.into(),
+            None => builtin_operator_name(self.builtin_code(n)).into(),
+        })
+    }
+
+    /// Returns the subgraph and node index where the `n`th entry in the
+    /// operator codes table is first used, or `None` if it is unused
+    pub(crate) fn first_use(&self, n: usize) -> Option<(usize, usize)> {
+        let model = self;
+        let mut subgraph = 0usize;
+        let mut node = 0usize;
+        let subgraph_ref = &mut subgraph;
+        let node_ref = &mut node;
+
+        let found = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  n as "size_t",
+                  subgraph_ref as "size_t*",
+                  node_ref as "size_t*"]
+                  -> bool as "bool" {
+                auto subgraphs = model->subgraphs();
+                if (subgraphs == nullptr) {
+                    return false;
+                }
+
+                for (size_t s = 0; s < subgraphs->size(); s++) {
+                    auto operators = subgraphs->Get(s)->operators();
+                    if (operators == nullptr) {
+                        continue;
+                    }
+
+                    for (size_t i = 0; i < operators->size(); i++) {
+                        if (operators->Get(i)->opcode_index() == n) {
+                            *subgraph_ref = s;
+                            *node_ref = i;
+                            return true;
+                        }
+                    }
+                }
+
+                return false;
+            })
+        };
+
+        if found {
+            Some((subgraph, node))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the tensor for the `n`th input or output of the
+    /// main subgraph, or `None` if there is no such input or output
+    pub(crate) fn io_tensor(&self, output: bool, n: usize) -> Option<usize> {
+        let model = self;
+        let index = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  output as "bool",
+                  n as "size_t"]
+                  -> i32 as "int32_t" {
+                auto subgraphs = model->subgraphs();
+                if (subgraphs == nullptr || subgraphs->size() == 0) {
+                    return -1;
+                }
+
+                auto subgraph = subgraphs->Get(0);
+                auto indices = output ? subgraph->outputs() : subgraph->inputs();
+                if (indices == nullptr || n >= indices->size()) {
+                    return -1;
+                }
+                return indices->Get(n);
+            })
+        };
+
+        if index < 0 {
+            None
+        } else {
+            Some(index as usize)
+        }
+    }
+
+    /// Returns the number of tensors in the main subgraph
+    pub(crate) fn tensors_len(&self) -> usize {
+        let model = self;
+        unsafe {
+            cpp!([model as "const tflite::Model*"] -> usize as "size_t" {
+                auto subgraphs = model->subgraphs();
+                if (subgraphs == nullptr || subgraphs->size() == 0) {
+                    return 0;
+                }
+
+                auto tensors = subgraphs->Get(0)->tensors();
+                return tensors == nullptr ? 0 : tensors->size();
+            })
+        }
+    }
+
+    /// Returns the number of outputs of the `node`th operator in the main
+    /// subgraph
+    pub(crate) fn node_outputs_len(&self, node: usize) -> usize {
+        let model = self;
+        unsafe {
+            cpp!([model as "const tflite::Model*", node as "size_t"]
+                  -> usize as "size_t" {
+                auto subgraphs = model->subgraphs();
+                if (subgraphs == nullptr || subgraphs->size() == 0) {
+                    return 0;
+                }
+
+                auto operators = subgraphs->Get(0)->operators();
+                if (operators == nullptr || node >= operators->size()) {
+                    return 0;
+                }
+
+                auto outputs = operators->Get(node)->outputs();
+                return outputs == nullptr ? 0 : outputs->size();
+            })
+        }
+    }
+
+    /// Returns the tensor index of the `n`th output of the `node`th
+    /// operator in the main subgraph, or `None` for an omitted optional
+    /// output
+    pub(crate) fn node_output(&self, node: usize, n: usize) -> Option<usize> {
+        assert!(n < self.node_outputs_len(node));
+
+        let model = self;
+        let index = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  node as "size_t",
+                  n as "size_t"]
+                  -> i32 as "int32_t" {
+                return model->subgraphs()->Get(0)->operators()->Get(node)
+                    ->outputs()->Get(n);
+            })
+        };
+
+        if index < 0 {
+            None
+        } else {
+            Some(index as usize)
+        }
+    }
+
+    /// Returns the name of the `tensor`th tensor in the main subgraph, or
+    /// `None` if it has no name or the name is not valid UTF8
+    pub(crate) fn tensor_name(&self, tensor: usize) -> Option<&str> {
+        let model = self;
+        let mut len = 0usize;
+        let len_ref = &mut len;
+
+        let name = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  tensor as "size_t",
+                  len_ref as "size_t*"]
+                  -> *const u8 as "const char*" {
+                auto subgraphs = model->subgraphs();
+                if (subgraphs == nullptr || subgraphs->size() == 0) {
+                    return nullptr;
+                }
+
+                auto tensors = subgraphs->Get(0)->tensors();
+                if (tensors == nullptr || tensor >= tensors->size()) {
+                    return nullptr;
+                }
+
+                auto name = tensors->Get(tensor)->name();
+                if (name == nullptr) {
+                    return nullptr;
+                }
+
+                *len_ref = name->size();
+                return name->c_str();
+            })
+        };
+
+        if name.is_null() {
+            return None;
+        }
+
+        let name = unsafe { slice::from_raw_parts(name, len) };
+        str::from_utf8(name).ok()
+    }
+}
+
+/// Returns the name of a builtin operator as given in the TensorFlow Lite
+/// schema, for example `"FULLY_CONNECTED"`
+pub(crate) fn builtin_operator_name(
+    code: BuiltinOperator::Type,
+) -> &'static str {
+    let name = unsafe {
+        cpp!([code as "int32_t"] -> *const cty::c_char as "const char*" {
+            return tflite::EnumNameBuiltinOperator(
+                static_cast<tflite::BuiltinOperator>(code));
+        })
+    };
+
+    // Names in the schema are plain ASCII. Codes outside the range known to
+    // the schema have an empty name
+    match unsafe { CStr::from_ptr(name) }.to_str() {
+        Ok("") | Err(_) => "UNKNOWN",
+        Ok(name) => name,
+    }
 }
 
 #[cfg(test)]
@@ -1,1338 +1,1831 @@
 /// Operators for Tensorflow micro
 ///
 /// See lite/micro/kernels/all_ops_resolver.cc
+use core::convert::TryFrom;
+use core::ffi::CStr;
+use core::iter::FromIterator;
+
+use crate::bindings::tflite::BuiltinOperator;
 use crate::micro_op_resolver::MutableOpResolver;
+use crate::model::builtin_operator_name;
+use crate::Error;
+
+cpp! {{
+    #include "op_resolver.hpp"
+}}
+
+/// An operator kernel that can be added to a
+/// [`MutableOpResolver`](crate::MutableOpResolver)
+///
+/// Each variant corresponds to one of the `add_*` methods, except `Custom`
+/// which is a custom operator added with
+/// [`add_custom`](crate::MutableOpResolver::add_custom).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Op {
+    Abs,
+    Add,
+    AddN,
+    ArgMax,
+    ArgMin,
+    AssignVariable,
+    AveragePool2D,
+    BatchMatMul,
+    BatchToSpaceNd,
+    BroadcastArgs,
+    BroadcastTo,
+    CallOnce,
+    Cast,
+    Ceil,
+    CircularBuffer,
+    Concatenation,
+    Conv2D,
+    Cos,
+    CumSum,
+    Delay,
+    DepthToSpace,
+    DepthwiseConv2D,
+    Dequantize,
+    DetectionPostprocess,
+    Div,
+    EmbeddingLookup,
+    Energy,
+    Elu,
+    Equal,
+    EthosU,
+    Exp,
+    ExpandDims,
+    FftAutoScale,
+    Fill,
+    FilterBank,
+    FilterBankLog,
+    FilterBankSquareRoot,
+    FilterBankSpectralSubtraction,
+    Floor,
+    FloorDiv,
+    FloorMod,
+    Framer,
+    FullyConnected,
+    Gather,
+    GatherNd,
+    Greater,
+    GreaterEqual,
+    HardSwish,
+    If,
+    Irfft,
+    L2Normalization,
+    L2Pool2D,
+    LeakyRelu,
+    Less,
+    LessEqual,
+    Log,
+    LogicalAnd,
+    LogicalNot,
+    LogicalOr,
+    Logistic,
+    LogSoftmax,
+    Maximum,
+    MaxPool2D,
+    MirrorPad,
+    Mean,
+    Minimum,
+    Mul,
+    Neg,
+    NotEqual,
+    OverlapAdd,
+    Pack,
+    PadV2,
+    Pcan,
+    Prelu,
+    Quantize,
+    ReadVariable,
+    ReduceMax,
+    Relu,
+    Relu6,
+    Reshape,
+    ResizeBilinear,
+    ResizeNearestNeighbor,
+    Rfft,
+    Round,
+    Rsqrt,
+    SelectV2,
+    Shape,
+    Sin,
+    Slice,
+    Softmax,
+    SpaceToBatchNd,
+    SpaceToDepth,
+    Split,
+    SplitV,
+    Squeeze,
+    Sqrt,
+    Square,
+    SquaredDifference,
+    StridedSlice,
+    Stacker,
+    Sub,
+    Sum,
+    Svdf,
+    Tanh,
+    TransposeConv,
+    Unpack,
+    UnidirectionalSequenceLSTM,
+    VarHandle,
+    While,
+    Window,
+    ZerosLike,
+    /// A custom operator implemented in Rust, registered under this name
+    Custom(&'static CStr),
+}
+
+// `from_builtin_code`, `from_custom_name`, `is_supported`, the conversion
+// to a builtin code and `MutableOpResolver::add`, from the list of operators
+// shared with `op_resolver_for!`
+tfmicro_macros::__operator_table!();
 
-impl MutableOpResolver {
+impl<const N: usize> MutableOpResolver<N> {
     /// Use the Abs operator in this op resolver
     pub fn add_abs(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Abs);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddAbs();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddAbs();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Add operator in this op resolver
     pub fn add_add(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Add);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddAdd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddAdd();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the AddN operator in this op resolver
     pub fn add_add_n(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::AddN);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddAddN();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddAddN();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ArgMax operator in this op resolver
     pub fn add_argmax(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ArgMax);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddArgMax();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddArgMax();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ArgMin operator in this op resolver
     pub fn add_argmin(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ArgMin);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddArgMin();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddArgMin();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the AssignVariable operator in this op resolver
     pub fn add_assign_variable(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::AssignVariable);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddAssignVariable();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddAssignVariable();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the AveragePool2D operator in this op resolver
     pub fn add_average_pool_2d(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::AveragePool2D);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddAveragePool2D();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddAveragePool2D();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the BatchMatMul operator in this op resolver
     pub fn add_batch_mat_mul(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::BatchMatMul);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddBatchMatMul();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddBatchMatMul();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the BatchToSpaceNd operator in this op resolver
     pub fn add_batch_to_space_nd(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::BatchToSpaceNd);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddBatchToSpaceNd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddBatchToSpaceNd();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the BroadcastArgs operator in this op resolver
     pub fn add_broadcast_args(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::BroadcastArgs);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddBroadcastArgs();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddBroadcastArgs();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the BroadcastTo operator in this op resolver
     pub fn add_broadcast_to(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::BroadcastTo);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddBroadcastTo();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddBroadcastTo();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the CallOnce operator in this op resolver
     pub fn add_call_once(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::CallOnce);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCallOnce();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCallOnce();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Cast operator in this op resolver
     pub fn add_cast(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Cast);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCast();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCast();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Ceil operator in this op resolver
     pub fn add_ceil(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Ceil);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCeil();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCeil();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the CircularBuffer operator in this op resolver
     pub fn add_circular_buffer(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::CircularBuffer);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCircularBuffer();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCircularBuffer();
+            return inner_ref->AddFrom(staging, "CIRCULAR_BUFFER");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Concatenation operator in this op resolver
     pub fn add_concatenation(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Concatenation);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddConcatenation();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddConcatenation();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Conv2D operator in this op resolver
     pub fn add_conv_2d(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Conv2D);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddConv2D();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddConv2D();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Cos operator in this op resolver
     pub fn add_cos(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Cos);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCos();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCos();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the CumSum operator in this op resolver
     pub fn add_cum_sum(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::CumSum);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddCumSum();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddCumSum();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Delay operator in this op resolver
     pub fn add_delay(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Delay);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDelay();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDelay();
+            return inner_ref->AddFrom(staging, "SignalDelay");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the DepthToSpace operator in this op resolver
     pub fn add_depth_to_space(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::DepthToSpace);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDepthToSpace();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDepthToSpace();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the DepthwiseConv2D operator in this op resolver
     pub fn add_depthwise_conv_2d(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::DepthwiseConv2D);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDepthwiseConv2D();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDepthwiseConv2D();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Dequantize operator in this op resolver
     pub fn add_dequantize(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Dequantize);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDequantize();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDequantize();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the DetectionPostprocess operator in this op resolver
     pub fn add_detection_postprocess(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::DetectionPostprocess);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDetectionPostprocess();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDetectionPostprocess();
+            return inner_ref->AddFrom(staging, "TFLite_Detection_PostProcess");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Div operator in this op resolver
     pub fn add_div(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Div);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddDiv();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddDiv();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the EmbeddingLookup operator in this op resolver
     pub fn add_embedding_lookup(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::EmbeddingLookup);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddEmbeddingLookup();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddEmbeddingLookup();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Energy operator in this op resolver
     pub fn add_energy(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Energy);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddEnergy();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddEnergy();
+            return inner_ref->AddFrom(staging, "SignalEnergy");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Elu operator in this op resolver
     pub fn add_elu(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Elu);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddElu();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddElu();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Equal operator in this op resolver
     pub fn add_equal(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Equal);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddEqual();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddEqual();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the EthosU operator in this op resolver
     pub fn add_ethos_u(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::EthosU);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddEthosU();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddEthosU();
+            return inner_ref->AddFrom(staging, "ethos-u");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Exp operator in this op resolver
     pub fn add_exp(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Exp);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddExp();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddExp();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ExpandDims operator in this op resolver
     pub fn add_expand_dims(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ExpandDims);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddExpandDims();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddExpandDims();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FftAutoScale operator in this op resolver
     pub fn add_fft_auto_scale(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FftAutoScale);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFftAutoScale();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFftAutoScale();
+            return inner_ref->AddFrom(staging, "SignalFftAutoScale");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Fill operator in this op resolver
     pub fn add_fill(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Fill);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFill();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFill();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FilterBank operator in this op resolver
     pub fn add_filter_bank(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FilterBank);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFilterBank();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFilterBank();
+            return inner_ref->AddFrom(staging, "SignalFilterBank");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FilterBankLog operator in this op resolver
     pub fn add_filter_bank_log(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FilterBankLog);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFilterBankLog();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFilterBankLog();
+            return inner_ref->AddFrom(staging, "SignalFilterBankLog");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FilterBankSquareRoot operator in this op resolver
     pub fn add_filter_bank_square_root(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FilterBankSquareRoot);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFilterBankSquareRoot();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFilterBankSquareRoot();
+            return inner_ref->AddFrom(staging, "SignalFilterBankSquareRoot");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FilterBankSpectralSubtraction operator in this op resolver
     pub fn add_filter_bank_spectral_subtraction(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FilterBankSpectralSubtraction);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFilterBankSpectralSubtraction();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFilterBankSpectralSubtraction();
+            return inner_ref->AddFrom(staging, "SignalFilterBankSpectralSubtraction");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Floor operator in this op resolver
     pub fn add_floor(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Floor);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFloor();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFloor();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FloorDiv operator in this op resolver
     pub fn add_floor_div(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FloorDiv);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFloorDiv();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFloorDiv();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FloorMod operator in this op resolver
     pub fn add_floor_mod(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FloorMod);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFloorMod();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFloorMod();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Framer operator in this op resolver
     pub fn add_framer(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Framer);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFramer();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFramer();
+            return inner_ref->AddFrom(staging, "SignalFramer");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the FullyConnected operator in this op resolver
     pub fn add_fully_connected(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::FullyConnected);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddFullyConnected();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddFullyConnected();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Gather operator in this op resolver
     pub fn add_gather(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Gather);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddGather();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddGather();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the GatherNd operator in this op resolver
     pub fn add_gather_nd(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::GatherNd);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddGatherNd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddGatherNd();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Greater operator in this op resolver
     pub fn add_greater(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Greater);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddGreater();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddGreater();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the GreaterEqual operator in this op resolver
     pub fn add_greater_equal(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::GreaterEqual);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddGreaterEqual();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddGreaterEqual();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the HardSwish operator in this op resolver
     pub fn add_hard_swish(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::HardSwish);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddHardSwish();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddHardSwish();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the If operator in this op resolver
     pub fn add_if(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::If);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddIf();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddIf();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Irfft operator in this op resolver
     pub fn add_irfft(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Irfft);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddIrfft();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddIrfft();
+            return inner_ref->AddFrom(staging, "SignalIrfft");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the L2Normalization operator in this op resolver
     pub fn add_l2_normalization(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::L2Normalization);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddL2Normalization();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddL2Normalization();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the L2Pool2D operator in this op resolver
     pub fn add_l2_pool_2d(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::L2Pool2D);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddL2Pool2D();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddL2Pool2D();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LeakyRelu operator in this op resolver
     pub fn add_leaky_relu(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LeakyRelu);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLeakyRelu();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLeakyRelu();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Less operator in this op resolver
     pub fn add_less(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Less);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLess();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLess();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LessEqual operator in this op resolver
     pub fn add_less_equal(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LessEqual);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLessEqual();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLessEqual();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Log operator in this op resolver
     pub fn add_log(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Log);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLog();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLog();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LogicalAnd operator in this op resolver
     pub fn add_logical_and(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LogicalAnd);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLogicalAnd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLogicalAnd();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LogicalNot operator in this op resolver
     pub fn add_logical_not(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LogicalNot);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLogicalNot();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLogicalNot();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LogicalOr operator in this op resolver
     pub fn add_logical_or(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LogicalOr);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLogicalOr();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLogicalOr();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Logistic operator in this op resolver
     pub fn add_logistic(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Logistic);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLogistic();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLogistic();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the LogSoftmax operator in this op resolver
     pub fn add_log_softmax(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::LogSoftmax);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddLogSoftmax();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddLogSoftmax();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Maximum operator in this op resolver
     pub fn add_maximum(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Maximum);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMaximum();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMaximum();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the MaxPool2D operator in this op resolver
     pub fn add_max_pool_2d(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::MaxPool2D);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMaxPool2D();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMaxPool2D();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the MirrorPad operator in this op resolver
     pub fn add_mirror_pad(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::MirrorPad);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMirrorPad();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMirrorPad();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Mean operator in this op resolver
     pub fn add_mean(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Mean);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMean();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMean();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Minimum operator in this op resolver
     pub fn add_minimum(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Minimum);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMinimum();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMinimum();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Mul operator in this op resolver
     pub fn add_mul(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Mul);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddMul();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddMul();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Neg operator in this op resolver
     pub fn add_neg(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Neg);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddNeg();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddNeg();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the NotEqual operator in this op resolver
     pub fn add_not_equal(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::NotEqual);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddNotEqual();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddNotEqual();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the OverlapAdd operator in this op resolver
     pub fn add_overlap_add(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::OverlapAdd);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddOverlapAdd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddOverlapAdd();
+            return inner_ref->AddFrom(staging, "SignalOverlapAdd");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Pack operator in this op resolver
     pub fn add_pack(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Pack);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddPack();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddPack();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the PadV2 operator in this op resolver
     pub fn add_pad_v2(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::PadV2);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddPadV2();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddPadV2();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the PCAN operator in this op resolver
     pub fn add_pcan(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Pcan);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddPCAN();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddPCAN();
+            return inner_ref->AddFrom(staging, "SignalPCAN");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Prelu operator in this op resolver
     pub fn add_prelu(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Prelu);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddPrelu();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddPrelu();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Quantize operator in this op resolver
     pub fn add_quantize(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Quantize);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddQuantize();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddQuantize();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ReadVariable operator in this op resolver
     pub fn add_read_variable(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ReadVariable);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddReadVariable();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddReadVariable();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ReduceMax operator in this op resolver
     pub fn add_reduce_max(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ReduceMax);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddReduceMax();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddReduceMax();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Relu operator in this op resolver
     pub fn add_relu(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Relu);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddRelu();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddRelu();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Relu6 operator in this op resolver
     pub fn add_relu6(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Relu6);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddRelu6();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddRelu6();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Reshape operator in this op resolver
     pub fn add_reshape(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Reshape);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddReshape();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddReshape();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ResizeBilinear operator in this op resolver
     pub fn add_resize_bilinear(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ResizeBilinear);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddResizeBilinear();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddResizeBilinear();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ResizeNearestNeighbor operator in this op resolver
     pub fn add_resize_nearest_neighbor(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ResizeNearestNeighbor);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddResizeNearestNeighbor();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddResizeNearestNeighbor();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Rfft operator in this op resolver
     pub fn add_rfft(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Rfft);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddRfft();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddRfft();
+            return inner_ref->AddFrom(staging, "SignalRfft");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Round operator in this op resolver
     pub fn add_round(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Round);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddRound();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddRound();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Rsqrt operator in this op resolver
     pub fn add_rsqrt(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Rsqrt);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddRsqrt();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddRsqrt();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the SelectV2 operator in this op resolver
     pub fn add_select_v2(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::SelectV2);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSelectV2();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSelectV2();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Shape operator in this op resolver
     pub fn add_shape(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Shape);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddShape();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddShape();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Sin operator in this op resolver
     pub fn add_sin(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Sin);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSin();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSin();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Slice operator in this op resolver
     pub fn add_slice(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Slice);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSlice();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSlice();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Softmax operator in this op resolver
     pub fn add_softmax(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Softmax);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSoftmax();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSoftmax();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the SpaceToBatchNd operator in this op resolver
     pub fn add_space_to_batch_nd(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::SpaceToBatchNd);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSpaceToBatchNd();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSpaceToBatchNd();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the SpaceToDepth operator in this op resolver
     pub fn add_space_to_depth(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::SpaceToDepth);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSpaceToDepth();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSpaceToDepth();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Split operator in this op resolver
     pub fn add_split(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Split);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSplit();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSplit();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the SplitV operator in this op resolver
     pub fn add_split_v(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::SplitV);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSplitV();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSplitV();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Squeeze operator in this op resolver
     pub fn add_squeeze(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Squeeze);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSqueeze();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSqueeze();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Sqrt operator in this op resolver
     pub fn add_sqrt(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Sqrt);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSqrt();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSqrt();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Square operator in this op resolver
     pub fn add_square(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Square);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSquare();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSquare();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the SquaredDifference operator in this op resolver
     pub fn add_squared_difference(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::SquaredDifference);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSquaredDifference();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSquaredDifference();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the StridedSlice operator in this op resolver
     pub fn add_strided_slice(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::StridedSlice);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddStridedSlice();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddStridedSlice();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Stacker operator in this op resolver
     pub fn add_stacker(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Stacker);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddStacker();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddStacker();
+            return inner_ref->AddFrom(staging, "SignalStacker");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Sub operator in this op resolver
     pub fn add_sub(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Sub);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSub();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSub();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Sum operator in this op resolver
     pub fn add_sum(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Sum);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSum();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSum();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Svdf operator in this op resolver
     pub fn add_svdf(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Svdf);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddSvdf();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddSvdf();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Tanh operator in this op resolver
     pub fn add_tanh(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Tanh);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddTanh();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddTanh();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the TransposeConv operator in this op resolver
     pub fn add_transpose_conv(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::TransposeConv);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddTransposeConv();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddTransposeConv();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Unpack operator in this op resolver
     pub fn add_unpack(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Unpack);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddUnpack();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddUnpack();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the UnidirectionalSquenceLSTM operator in this op resolver
     pub fn add_unidirectional_sequence_lstm(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::UnidirectionalSequenceLSTM);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddUnidirectionalSequenceLSTM();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddUnidirectionalSequenceLSTM();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the VarHandle operator in this op resolver
     pub fn add_var_handle(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::VarHandle);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddVarHandle();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddVarHandle();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the While operator in this op resolver
     pub fn add_while(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::While);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddWhile();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddWhile();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the Window operator in this op resolver
     pub fn add_window(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::Window);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddWindow();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddWindow();
+            return inner_ref->AddFrom(staging, "SignalWindow");
         });
 
-        self
+        self.registered(added)
     }
 
     /// Use the ZerosLike operator in this op resolver
     pub fn add_zeros_like(mut self) -> Self {
-        self.check_then_inc_len();
-        let inner_ref = &mut self.inner;
+        self.check_can_add(Op::ZerosLike);
+        let inner_ref = &mut self.inner.resolver;
 
-        cpp!(unsafe [inner_ref as "tflite::MicroMutableOpResolver<128>*"] {
-            inner_ref->AddZerosLike();
+        let added = cpp!(unsafe [inner_ref as "TfMicroOpResolver*"]
+                         -> bool as "bool" {
+            tflite::MicroMutableOpResolver<1> staging;
+            staging.AddZerosLike();
+            return inner_ref->AddFrom(staging);
         });
 
-        self
+        self.registered(added)
+    }
+}
+
+impl<const N: usize> FromIterator<Op> for MutableOpResolver<N> {
+    fn from_iter<I: IntoIterator<Item = Op>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::add)
+    }
+}
+
+/// Convert from a builtin operator code in the flatbuffer schema
+impl TryFrom<BuiltinOperator::Type> for Op {
+    type Error = Error;
+
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperator` if there is no binding for this
+    /// builtin operator, including for `BuiltinOperator_CUSTOM`.
+    fn try_from(code: BuiltinOperator::Type) -> Result<Self, Error> {
+        Op::from_builtin_code(code).ok_or_else(|| {
+            Error::UnsupportedOperator(builtin_operator_name(code).into())
+        })
     }
 }
@@ -0,0 +1,357 @@
+//! Profiling the operators run by [`invoke`](crate::MicroInterpreter::invoke)
+//!
+//! Create an interpreter with
+//! [`MicroInterpreter::new_profiled`](crate::MicroInterpreter::new_profiled)
+//! and then call
+//! [`invoke_profiled`](crate::MicroInterpreter::invoke_profiled) with a
+//! [`Profiler`]. TensorFlow micro reports an event for each operator it runs.
+//!
+//! [`OpProfiler`] records these events, timed by any [`TickSource`] such as
+//! a cycle counter:
+//!
+//! ```ignore
+//! let mut profiler = OpProfiler::<_, 32>::new(|| DWT::cycle_count() as u64);
+//!
+//! interpreter.invoke_profiled(&mut profiler).unwrap();
+//!
+//! for event in profiler.events() {
+//!     info!("{} {}: {} cycles", event.node, event.tag, event.ticks);
+//! }
+//! ```
+
+use core::ffi::CStr;
+use core::fmt;
+
+use crate::micro_interpreter::{MicroInterpreter, Options};
+use crate::micro_op_resolver::OpResolver;
+use crate::model::Model;
+use crate::Error;
+
+cpp! {{
+    #include "profiler.hpp"
+}}
+
+/// Receives profiling events from TensorFlow micro, as its
+/// `MicroProfilerInterface`
+pub trait Profiler {
+    /// Called when TensorFlow micro begins an event, typically running an
+    /// operator. `tag` is the name of the operator, and `node` is the
+    /// number of events since the start of the invoke, which is the node
+    /// index for models with a single subgraph.
+    ///
+    /// Returns a handle that is passed to [`end_event`](Self::end_event).
+    fn begin_event(&mut self, tag: &'static str, node: usize) -> u32;
+
+    /// Called when the event with `handle` ends
+    fn end_event(&mut self, handle: u32);
+}
+
+/// A source of ticks for timing events, such as a cycle counter or a timer
+pub trait TickSource {
+    /// Returns the current tick count
+    fn ticks(&mut self) -> u64;
+}
+impl<F: FnMut() -> u64> TickSource for F {
+    fn ticks(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Ticks of a host clock, in nanoseconds since creation
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct InstantTicks(std::time::Instant);
+#[cfg(feature = "std")]
+impl InstantTicks {
+    /// Start counting ticks from now
+    pub fn new() -> Self {
+        Self(std::time::Instant::now())
+    }
+}
+#[cfg(feature = "std")]
+impl Default for InstantTicks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl TickSource for InstantTicks {
+    fn ticks(&mut self) -> u64 {
+        self.0.elapsed().as_nanos() as u64
+    }
+}
+
+/// A timed event, typically running one operator
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProfileEvent {
+    /// Name of the operator
+    pub tag: &'static str,
+    /// Index of the event within the invoke, see
+    /// [`Profiler::begin_event`]
+    pub node: usize,
+    /// Ticks elapsed between the beginning and end of the event
+    pub ticks: u64,
+}
+
+/// A [`Profiler`] that records the first `N` events, timed by a
+/// [`TickSource`]
+pub struct OpProfiler<T, const N: usize> {
+    tick_source: T,
+    events: [ProfileEvent; N],
+    len: usize,
+    dropped: usize,
+}
+impl<T, const N: usize> fmt::Debug for OpProfiler<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpProfiler")
+            .field("events", &self.events())
+            .field("dropped", &self.dropped)
+            .finish()
+    }
+}
+
+impl<T: TickSource, const N: usize> OpProfiler<T, N> {
+    /// Create a new OpProfiler, timing events with `tick_source`
+    pub fn new(tick_source: T) -> Self {
+        let event = ProfileEvent {
+            tag: "",
+            node: 0,
+            ticks: 0,
+        };
+
+        Self {
+            tick_source,
+            events: [event; N],
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// The events recorded, in the order they began
+    pub fn events(&self) -> &[ProfileEvent] {
+        &self.events[..self.len]
+    }
+
+    /// Returns the number of events that were not recorded, because the
+    /// profiler was full
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Returns the total ticks of all recorded events
+    pub fn total_ticks(&self) -> u64 {
+        self.events().iter().map(|e| e.ticks).sum()
+    }
+
+    /// Clear the recorded events
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.dropped = 0;
+    }
+}
+
+impl<T: TickSource, const N: usize> Profiler for OpProfiler<T, N> {
+    fn begin_event(&mut self, tag: &'static str, node: usize) -> u32 {
+        if self.len == N {
+            self.dropped += 1;
+            return u32::MAX;
+        }
+
+        // Holds the start tick until the event ends
+        self.events[self.len] = ProfileEvent {
+            tag,
+            node,
+            ticks: self.tick_source.ticks(),
+        };
+        self.len += 1;
+        (self.len - 1) as u32
+    }
+
+    fn end_event(&mut self, handle: u32) {
+        let end = self.tick_source.ticks();
+
+        if let Some(event) = self.events[..self.len].get_mut(handle as usize) {
+            event.ticks = end.saturating_sub(event.ticks);
+        }
+    }
+}
+
+type BeginEventFn =
+    unsafe extern "C" fn(*mut cty::c_void, *const cty::c_char, usize) -> u32;
+type EndEventFn = unsafe extern "C" fn(*mut cty::c_void, u32);
+
+unsafe extern "C" fn begin_event_trampoline<P: Profiler>(
+    profiler: *mut cty::c_void,
+    tag: *const cty::c_char,
+    node: usize,
+) -> u32 {
+    // Tags are operator names, which are string literals in TensorFlow
+    // micro or the `&'static CStr` of a custom operator
+    let tag: &'static str = if tag.is_null() {
+        ""
+    } else {
+        CStr::from_ptr(tag).to_str().unwrap_or("?")
+    };
+
+    (*(profiler as *mut P)).begin_event(tag, node)
+}
+
+unsafe extern "C" fn end_event_trampoline<P: Profiler>(
+    profiler: *mut cty::c_void,
+    handle: u32,
+) {
+    (*(profiler as *mut P)).end_event(handle)
+}
+
+impl<'a> MicroInterpreter<'a> {
+    /// Create a new micro_interpreter that can be profiled with
+    /// [`invoke_profiled`](Self::invoke_profiled)
+    ///
+    /// # Errors
+    ///
+    /// As for [`new`](Self::new).
+    pub fn new_profiled<'m: 'a, R: OpResolver>(
+        model: &'m Model,
+        resolver: R,
+        tensor_arena: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        Self::create(
+            model,
+            &resolver,
+            tensor_arena,
+            Options {
+                profiling: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Runs the model as [`invoke`](Self::invoke), reporting each operator
+    /// to `profiler`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotProfiled` if this interpreter was not created with
+    /// [`new_profiled`](Self::new_profiled).
+    ///
+    /// Returns `Error::InvokeError` with a TensorFlow
+    /// [`Status`](crate::Status) if an error occours in TensorFlow.
+    pub fn invoke_profiled<P: Profiler>(
+        &mut self,
+        profiler: &mut P,
+    ) -> Result<(), Error> {
+        if self.profiler.is_null() {
+            return Err(Error::NotProfiled);
+        }
+
+        let shim = self.profiler;
+        let profiler = profiler as *mut P as *mut cty::c_void;
+        let begin_event: BeginEventFn = begin_event_trampoline::<P>;
+        let end_event: EndEventFn = end_event_trampoline::<P>;
+
+        unsafe {
+            cpp!([shim as "TfMicroProfiler*",
+                  profiler as "void*",
+                  begin_event as "TfMicroBeginEventFn",
+                  end_event as "TfMicroEndEventFn"] {
+                shim->Attach(profiler, begin_event, end_event);
+            });
+        }
+
+        let result = self.invoke();
+
+        // `profiler` is only borrowed for this call
+        unsafe {
+            cpp!([shim as "TfMicroProfiler*"] {
+                shim->Detach();
+            });
+        }
+
+        result.map_err(Error::InvokeError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    #[test]
+    fn invoke_profiled() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/micro_speech/models/micro_speech_quantized.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_depthwise_conv_2d()
+            .add_fully_connected()
+            .add_softmax()
+            .add_reshape();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 10 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter = MicroInterpreter::new_profiled(
+            model,
+            op_resolver,
+            &mut tensor_arena[..],
+        )
+        .unwrap();
+
+        // Each event takes one tick
+        let mut tick = 0;
+        let mut profiler = OpProfiler::<_, 8>::new(|| {
+            tick += 1;
+            tick
+        });
+
+        interpreter.invoke_profiled(&mut profiler).unwrap();
+
+        let events = profiler.events();
+        assert!(!events.is_empty());
+        assert_eq!(profiler.dropped(), 0);
+        for (n, event) in events.iter().enumerate() {
+            assert_eq!(event.node, n);
+            assert_eq!(event.ticks, 1);
+        }
+        assert!(events.iter().any(|e| e.tag == "SOFTMAX"));
+
+        // Invoking without the profiler records nothing more
+        interpreter.invoke().unwrap();
+        assert_eq!(profiler.events().len(), events.len());
+    }
+
+    #[test]
+    fn invoke_profiled_not_profiled() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_int8.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        let mut profiler = OpProfiler::<_, 8>::new(|| 0);
+        assert_eq!(
+            interpreter.invoke_profiled(&mut profiler),
+            Err(Error::NotProfiled)
+        );
+        assert!(profiler.events().is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+//! Signatures of a model, naming its inputs and outputs
+//!
+//! Models exported from TensorFlow 2 contain `SignatureDef`s, which give the
+//! inputs and outputs of a subgraph stable names such as `"x"` or
+//! `"Identity"`. Looking up tensors by these names keeps working when the
+//! model is re-exported and the order of its tensors changes.
+
+use core::fmt;
+use core::{slice, str};
+
+use crate::micro_interpreter::MicroInterpreter;
+use crate::model::Model;
+use crate::tensor::{ElemTypeOf, Tensor};
+use crate::Error;
+
+cpp! {{
+    #include "tensorflow/lite/schema/schema_generated.h"
+}}
+
+/// A signature of a model, see [`Model::signatures`]
+#[derive(Clone, Copy)]
+pub struct Signature<'m> {
+    model: &'m Model,
+    index: usize,
+}
+
+/// A named input or output of a [`Signature`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SignatureTensor<'m> {
+    /// Name of the input or output in the signature
+    pub name: &'m str,
+    /// Index of the tensor in the subgraph of the signature
+    pub tensor_index: usize,
+}
+
+impl Model {
+    /// Returns an iterator over the signatures of this model
+    ///
+    /// Models converted from TensorFlow 1, and many older models, have no
+    /// signatures.
+    pub fn signatures(&self) -> impl Iterator<Item = Signature<'_>> + '_ {
+        let model = self;
+        let len = unsafe {
+            cpp!([model as "const tflite::Model*"] -> usize as "size_t" {
+                auto signatures = model->signature_defs();
+                return signatures == nullptr ? 0 : signatures->size();
+            })
+        };
+
+        (0..len).map(move |index| Signature { model, index })
+    }
+}
+
+impl<'m> Signature<'m> {
+    /// The key of this signature, for example `"serving_default"`
+    pub fn key(&self) -> Option<&'m str> {
+        let model = self.model;
+        let index = self.index;
+        let mut len = 0usize;
+        let len_ref = &mut len;
+
+        let key = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  index as "size_t",
+                  len_ref as "size_t*"]
+                  -> *const u8 as "const char*" {
+                auto key = model->signature_defs()->Get(index)->signature_key();
+                if (key == nullptr) {
+                    return nullptr;
+                }
+
+                *len_ref = key->size();
+                return key->c_str();
+            })
+        };
+
+        if key.is_null() {
+            return None;
+        }
+
+        let key = unsafe { slice::from_raw_parts(key, len) };
+        str::from_utf8(key).ok()
+    }
+
+    /// Index of the subgraph that this signature describes
+    pub fn subgraph_index(&self) -> usize {
+        let model = self.model;
+        let index = self.index;
+
+        unsafe {
+            cpp!([model as "const tflite::Model*", index as "size_t"]
+                  -> usize as "size_t" {
+                return model->signature_defs()->Get(index)->subgraph_index();
+            })
+        }
+    }
+
+    /// Returns an iterator over the named inputs of this signature
+    pub fn inputs(&self) -> impl Iterator<Item = SignatureTensor<'m>> {
+        self.tensors(false)
+    }
+
+    /// Returns an iterator over the named outputs of this signature
+    pub fn outputs(&self) -> impl Iterator<Item = SignatureTensor<'m>> {
+        self.tensors(true)
+    }
+
+    /// The named inputs or outputs. Names that are not valid UTF8 are
+    /// skipped
+    fn tensors(
+        &self,
+        outputs: bool,
+    ) -> impl Iterator<Item = SignatureTensor<'m>> {
+        let signature = *self;
+        let len = signature.tensors_len(outputs);
+
+        (0..len).filter_map(move |n| signature.tensor(outputs, n))
+    }
+
+    fn tensors_len(&self, outputs: bool) -> usize {
+        let model = self.model;
+        let index = self.index;
+
+        unsafe {
+            cpp!([model as "const tflite::Model*",
+                  index as "size_t",
+                  outputs as "bool"]
+                  -> usize as "size_t" {
+                auto signature = model->signature_defs()->Get(index);
+                auto tensors =
+                    outputs ? signature->outputs() : signature->inputs();
+                return tensors == nullptr ? 0 : tensors->size();
+            })
+        }
+    }
+
+    fn tensor(&self, outputs: bool, n: usize) -> Option<SignatureTensor<'m>> {
+        let model = self.model;
+        let index = self.index;
+        let mut len = 0usize;
+        let mut tensor_index = 0usize;
+        let len_ref = &mut len;
+        let tensor_index_ref = &mut tensor_index;
+
+        let name = unsafe {
+            cpp!([model as "const tflite::Model*",
+                  index as "size_t",
+                  outputs as "bool",
+                  n as "size_t",
+                  len_ref as "size_t*",
+                  tensor_index_ref as "size_t*"]
+                  -> *const u8 as "const char*" {
+                auto signature = model->signature_defs()->Get(index);
+                auto tensor = (outputs ? signature->outputs()
+                                       : signature->inputs())->Get(n);
+                if (tensor->name() == nullptr) {
+                    return nullptr;
+                }
+
+                *tensor_index_ref = tensor->tensor_index();
+                *len_ref = tensor->name()->size();
+                return tensor->name()->c_str();
+            })
+        };
+
+        if name.is_null() {
+            return None;
+        }
+
+        let name = unsafe { slice::from_raw_parts(name, len) };
+        Some(SignatureTensor {
+            name: str::from_utf8(name).ok()?,
+            tensor_index,
+        })
+    }
+}
+
+impl fmt::Debug for Signature<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Tensors<'s, 'm>(&'s Signature<'m>, bool);
+        impl fmt::Debug for Tensors<'_, '_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.0.tensors(self.1)).finish()
+            }
+        }
+
+        f.debug_struct("Signature")
+            .field("key", &self.key())
+            .field("subgraph_index", &self.subgraph_index())
+            .field("inputs", &Tensors(self, false))
+            .field("outputs", &Tensors(self, true))
+            .finish()
+    }
+}
+
+impl<'a> MicroInterpreter<'a> {
+    /// Returns the index of the input tensor called `name`, for use with
+    /// [`input`](Self::input)
+    ///
+    /// `name` is looked up in the signatures of the main subgraph. If it is
+    /// not found there, or the model has no signatures, it is compared with
+    /// the names of the input tensors instead.
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        self.io_index(false, name)
+    }
+
+    /// Returns the index of the output tensor called `name`, for use with
+    /// [`output`](Self::output)
+    ///
+    /// `name` is looked up as for [`input_index`](Self::input_index).
+    pub fn output_index(&self, name: &str) -> Option<usize> {
+        self.io_index(true, name)
+    }
+
+    /// Clones data into the input tensor called `name`
+    ///
+    /// ```ignore
+    /// interpreter.input_by_name("x", &[0.5f32])?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TensorNotFound` if there is no input tensor called
+    /// `name`, see [`input_index`](Self::input_index).
+    ///
+    /// Otherwise as for [`input`](Self::input).
+    pub fn input_by_name<T: ElemTypeOf + Clone>(
+        &mut self,
+        name: &str,
+        data: &[T],
+    ) -> Result<(), Error> {
+        let n = self.input_index(name).ok_or(Error::TensorNotFound)?;
+        self.input(n, data)
+    }
+
+    /// Returns an immutable reference to the output tensor called `name`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TensorNotFound` if there is no output tensor called
+    /// `name`, see [`output_index`](Self::output_index).
+    pub fn output_by_name(&self, name: &str) -> Result<&Tensor, Error> {
+        let n = self.output_index(name).ok_or(Error::TensorNotFound)?;
+        self.output(n)
+    }
+
+    /// Index of the input or output called `name`
+    fn io_index(&self, outputs: bool, name: &str) -> Option<usize> {
+        let model = self.model;
+        let len = if outputs {
+            self.outputs_size()
+        } else {
+            self.inputs_size()
+        };
+
+        // The position of a subgraph tensor among the inputs or outputs
+        let position = |tensor_index: usize| {
+            (0..len)
+                .find(|&n| model.io_tensor(outputs, n) == Some(tensor_index))
+        };
+
+        let from_signature = model
+            .signatures()
+            .filter(|signature| signature.subgraph_index() == 0)
+            .flat_map(|signature| signature.tensors(outputs))
+            .find(|tensor| tensor.name == name)
+            .and_then(|tensor| position(tensor.tensor_index));
+
+        from_signature.or_else(|| {
+            (0..len).find(|&n| {
+                model
+                    .io_tensor(outputs, n)
+                    .and_then(|tensor| model.tensor_name(tensor))
+                    == Some(name)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    #[test]
+    fn output_by_tensor_name() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_int8.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        // Tensor names resolve to their index
+        let input = model.tensor_name(model.io_tensor(false, 0).unwrap());
+        let input = input.unwrap();
+        assert_eq!(interpreter.input_index(input), Some(0));
+        assert_eq!(interpreter.input_info(0).unwrap().name, Some(input));
+        let output = model.tensor_name(model.io_tensor(true, 0).unwrap());
+        let output = output.unwrap();
+        assert_eq!(interpreter.output_index(output), Some(0));
+
+        // Signature names, if any, also resolve
+        for signature in model.signatures() {
+            for tensor in signature.inputs() {
+                assert_eq!(interpreter.input_index(tensor.name), Some(0));
+            }
+        }
+
+        interpreter.input_by_name(input, &[0i8]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(
+            interpreter
+                .output_by_name(output)
+                .unwrap()
+                .as_data::<i8>()
+                .len(),
+            1
+        );
+
+        assert_eq!(interpreter.input_index("missing"), None);
+        assert_eq!(
+            interpreter.output_by_name("missing").err(),
+            Some(Error::TensorNotFound)
+        );
+    }
+
+    #[test]
+    fn io_by_signature_name() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model, with a serving_default signature
+        let model = include_bytes!("../tests/models/signature_add.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        let signature = model.signatures().next().unwrap();
+        assert_eq!(signature.key(), Some("serving_default"));
+        assert_eq!(signature.subgraph_index(), 0);
+        assert_eq!(signature.inputs().count(), 2);
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty().add_add();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        // The inputs are in the order y, x
+        assert_eq!(interpreter.input_index("x"), Some(1));
+        assert_eq!(interpreter.input_index("y"), Some(0));
+        assert_eq!(interpreter.output_index("Identity"), Some(0));
+
+        // Tensor names still resolve
+        assert_eq!(interpreter.input_index("serving_default_x:0"), Some(1));
+
+        interpreter.input_by_name("x", &[1.0f32, 2.0]).unwrap();
+        interpreter.input_by_name("y", &[10.0f32, 20.0]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(
+            interpreter
+                .output_by_name("Identity")
+                .unwrap()
+                .as_data::<f32>(),
+            [11.0, 22.0]
+        );
+    }
+}
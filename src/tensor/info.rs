@@ -8,17 +8,79 @@ use crate::Error;
 
 use super::ElementType;
 
+/// Per-tensor quantization parameters
+///
+/// A quantized value `q` represents the real value
+/// `scale * (q - zero_point)`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct QuantizationParams {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+/// Affine quantization parameters, which may be per-channel
+///
+/// Per-tensor quantization has one scale and zero point. Per-channel
+/// quantization, as used for the weights of convolutions, has one scale and
+/// zero point for each index along `quantized_dimension`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AffineQuantization<'a> {
+    pub scale: &'a [f32],
+    pub zero_point: &'a [i32],
+    /// The dimension indexed by the channel, if per-channel
+    pub quantized_dimension: usize,
+}
+
+impl AffineQuantization<'_> {
+    /// Returns `true` if there is more than one scale
+    pub fn is_per_channel(&self) -> bool {
+        self.scale.len() > 1
+    }
+
+    /// The quantization parameters of the `channel`th channel, or of the
+    /// whole tensor if it is quantized per-tensor
+    ///
+    /// Returns `None` if there is no such channel.
+    pub fn channel(&self, channel: usize) -> Option<QuantizationParams> {
+        let channel = if self.is_per_channel() { channel } else { 0 };
+
+        Some(QuantizationParams {
+            scale: *self.scale.get(channel)?,
+            // Zero points are sometimes given once for all channels
+            zero_point: match self.zero_point {
+                [zero_point] => *zero_point,
+                zero_points => *zero_points.get(channel)?,
+            },
+        })
+    }
+}
+
 /// Metadata describing a tensor
 pub struct TensorInfo<'a> {
+    /// Name of the tensor in the model, if known. Only tensors obtained
+    /// from the interpreter by index are named
+    pub name: Option<&'a str>,
     pub element_type: ElementType,
     pub dims: &'a [i32],
+    /// Size of the tensor data in bytes
+    pub bytes: usize,
+    /// Quantization parameters, or `None` if the tensor is not quantized
+    /// per-tensor
+    pub quantization: Option<QuantizationParams>,
+    /// Affine quantization parameters, including those of per-channel
+    /// quantization, or `None` if the tensor has none
+    pub affine_quantization: Option<AffineQuantization<'a>>,
 }
 
 impl fmt::Debug for TensorInfo<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TensorInfo")
+            .field("name", &self.name)
             .field("element_type", &self.element_type)
             .field("dims", &self.dims)
+            .field("bytes", &self.bytes)
+            .field("quantization", &self.quantization)
+            .field("affine_quantization", &self.affine_quantization)
             .finish()
     }
 }
@@ -33,12 +95,97 @@ impl<'a> TryFrom<&'a bindings::TfLiteTensor> for TensorInfo<'a> {
             .try_into()
             .or(Err(Error::ElementTypeUnimplemented))?;
 
+        // A scale of zero means the tensor is not quantized
+        let quantization = if t.params.scale != 0.0 {
+            Some(QuantizationParams {
+                scale: t.params.scale,
+                zero_point: t.params.zero_point,
+            })
+        } else {
+            None
+        };
+
         Ok(Self {
+            name: None,
             element_type,
             dims: unsafe {
                 let dims = &*t.dims;
                 dims.data.as_slice(dims.size as usize)
             },
+            bytes: t.bytes,
+            quantization,
+            affine_quantization: affine_quantization(&t.quantization),
         })
     }
 }
+
+/// The affine quantization parameters in `quantization`, if any
+fn affine_quantization(
+    quantization: &bindings::TfLiteQuantization,
+) -> Option<AffineQuantization<'_>> {
+    if quantization.type_
+        != bindings::TfLiteQuantizationType::kTfLiteAffineQuantization
+        || quantization.params.is_null()
+    {
+        return None;
+    }
+
+    // Allocated in the tensor arena, or in the model
+    let params = unsafe {
+        &*(quantization.params as *const bindings::TfLiteAffineQuantization)
+    };
+    if params.scale.is_null() || params.zero_point.is_null() {
+        return None;
+    }
+
+    let (scale, zero_point) = unsafe {
+        let scale = &*params.scale;
+        let zero_point = &*params.zero_point;
+        (
+            scale.data.as_slice(scale.size as usize),
+            zero_point.data.as_slice(zero_point.size as usize),
+        )
+    };
+
+    Some(AffineQuantization {
+        scale,
+        zero_point,
+        quantized_dimension: params.quantized_dimension as usize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn affine_quantization_channel() {
+        let per_channel = AffineQuantization {
+            scale: &[0.5, 0.25],
+            zero_point: &[0],
+            quantized_dimension: 3,
+        };
+        assert!(per_channel.is_per_channel());
+        assert_eq!(
+            per_channel.channel(1),
+            Some(QuantizationParams {
+                scale: 0.25,
+                zero_point: 0
+            })
+        );
+        assert_eq!(per_channel.channel(2), None);
+
+        let per_tensor = AffineQuantization {
+            scale: &[0.1],
+            zero_point: &[-128],
+            quantized_dimension: 0,
+        };
+        assert_eq!(
+            per_tensor.channel(5),
+            Some(QuantizationParams {
+                scale: 0.1,
+                zero_point: -128
+            })
+        );
+    }
+}
@@ -6,9 +6,13 @@ use core::slice;
 use ordered_float::NotNan;
 
 use crate::bindings;
+use crate::Error;
 
 mod info;
-pub use info::TensorInfo;
+#[cfg(feature = "std")]
+mod npy;
+mod quantize;
+pub use info::{AffineQuantization, QuantizationParams, TensorInfo};
 
 /// A TensorFlow Tensor
 #[repr(transparent)]
@@ -22,6 +26,24 @@ pub enum ElementType {
     UInt8,
     Int32,
     Int8,
+    Int16,
+    UInt16,
+    Int64,
+    UInt32,
+    UInt64,
+    /// Booleans stored one to a byte. Accessed with
+    /// [`Tensor::bool_data`](crate::tensor::Tensor::bool_data), as a byte
+    /// may not be a valid `bool`
+    Bool,
+    /// Half precision floats, accessed as `half::f16` with the `half`
+    /// feature
+    Float16,
+    Float64,
+    /// Pairs of `f32`, accessed as [`Complex64`](crate::tensor::Complex64)
+    Complex64,
+    /// Signed 4-bit integers, packed two to a byte. Accessed with
+    /// [`Tensor::int4_data`](crate::tensor::Tensor::int4_data)
+    Int4,
 }
 impl TryFrom<bindings::TfLiteType> for ElementType {
     type Error = bindings::TfLiteType;
@@ -34,6 +56,16 @@ impl TryFrom<bindings::TfLiteType> for ElementType {
             bindings::TfLiteType::kTfLiteUInt8 => Ok(UInt8),
             bindings::TfLiteType::kTfLiteInt32 => Ok(Int32),
             bindings::TfLiteType::kTfLiteInt8 => Ok(Int8),
+            bindings::TfLiteType::kTfLiteInt16 => Ok(Int16),
+            bindings::TfLiteType::kTfLiteUInt16 => Ok(UInt16),
+            bindings::TfLiteType::kTfLiteInt64 => Ok(Int64),
+            bindings::TfLiteType::kTfLiteUInt32 => Ok(UInt32),
+            bindings::TfLiteType::kTfLiteUInt64 => Ok(UInt64),
+            bindings::TfLiteType::kTfLiteBool => Ok(Bool),
+            bindings::TfLiteType::kTfLiteFloat16 => Ok(Float16),
+            bindings::TfLiteType::kTfLiteFloat64 => Ok(Float64),
+            bindings::TfLiteType::kTfLiteComplex64 => Ok(Complex64),
+            bindings::TfLiteType::kTfLiteInt4 => Ok(Int4),
             t => Err(t),
         }
     }
@@ -69,6 +101,55 @@ impl ElemTypeOf for i8 {
         ElementType::Int8
     }
 }
+impl ElemTypeOf for i16 {
+    fn elem_type_of() -> ElementType {
+        ElementType::Int16
+    }
+}
+impl ElemTypeOf for u16 {
+    fn elem_type_of() -> ElementType {
+        ElementType::UInt16
+    }
+}
+impl ElemTypeOf for i64 {
+    fn elem_type_of() -> ElementType {
+        ElementType::Int64
+    }
+}
+impl ElemTypeOf for u32 {
+    fn elem_type_of() -> ElementType {
+        ElementType::UInt32
+    }
+}
+impl ElemTypeOf for u64 {
+    fn elem_type_of() -> ElementType {
+        ElementType::UInt64
+    }
+}
+#[cfg(feature = "half")]
+impl ElemTypeOf for half::f16 {
+    fn elem_type_of() -> ElementType {
+        ElementType::Float16
+    }
+}
+impl ElemTypeOf for f64 {
+    fn elem_type_of() -> ElementType {
+        ElementType::Float64
+    }
+}
+impl ElemTypeOf for Complex64 {
+    fn elem_type_of() -> ElementType {
+        ElementType::Complex64
+    }
+}
+
+/// A complex number, as the elements of a `Complex64` tensor
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Complex64 {
+    pub re: f32,
+    pub im: f32,
+}
 
 /// Implement From raw types to Tensor
 impl From<*mut bindings::TfLiteTensor> for &Tensor {
@@ -163,4 +244,157 @@ impl Tensor {
             )
         }
     }
+
+    /// Returns an iterator over the elements of an Int4 tensor, unpacked
+    /// and sign extended
+    ///
+    /// Elements are packed two to a byte, the first in the low nibble.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if this is not an Int4 tensor.
+    pub fn int4_data(&self) -> impl ExactSizeIterator<Item = i8> + '_ {
+        let packed = self.raw_bytes(ElementType::Int4);
+
+        (0..self.flat_len()).map(move |i| unpack_int4(packed, i))
+    }
+
+    /// Packs `data` into this Int4 tensor
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputDataLenMismatch` if the length of `data` does
+    /// not match the flat length of this tensor.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if this is not an Int4 tensor, or if an
+    /// element of `data` is outside the range `-8..=7`.
+    pub fn set_int4_data(&mut self, data: &[i8]) -> Result<(), Error> {
+        if data.len() != self.flat_len() {
+            return Err(Error::InputDataLenMismatch);
+        }
+
+        let packed = self.raw_bytes_mut(ElementType::Int4);
+        for (byte, pair) in packed.iter_mut().zip(data.chunks(2)) {
+            *byte = pack_int4(pair);
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over the elements of a Bool tensor
+    ///
+    /// Elements are stored one to a byte. Any non-zero byte is `true`, so
+    /// this is safe whatever the tensor holds.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if this is not a Bool tensor.
+    pub fn bool_data(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        self.raw_bytes(ElementType::Bool).iter().map(|&b| b != 0)
+    }
+
+    /// Stores `data` in this Bool tensor, as bytes of 0 or 1
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputDataLenMismatch` if the length of `data` does
+    /// not match the flat length of this tensor.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if this is not a Bool tensor.
+    pub fn set_bool_data(&mut self, data: &[bool]) -> Result<(), Error> {
+        if data.len() != self.flat_len() {
+            return Err(Error::InputDataLenMismatch);
+        }
+
+        let bytes = self.raw_bytes_mut(ElementType::Bool);
+        for (byte, &x) in bytes.iter_mut().zip(data) {
+            *byte = x as u8;
+        }
+        Ok(())
+    }
+
+    /// The bytes of a tensor of `element_type`, for types that have no
+    /// [`ElemTypeOf`]
+    fn raw_bytes(&self, element_type: ElementType) -> &[u8] {
+        assert!(
+            self.element_type() == Some(element_type),
+            "Type `{:?}` does not match the original type `{:?}`",
+            element_type,
+            self.0.type_
+        );
+
+        unsafe {
+            slice::from_raw_parts(
+                self.0.data.raw_const as *const u8,
+                self.0.bytes,
+            )
+        }
+    }
+
+    /// The bytes of a tensor of `element_type`, mutably
+    fn raw_bytes_mut(&mut self, element_type: ElementType) -> &mut [u8] {
+        assert!(
+            self.element_type() == Some(element_type),
+            "Type `{:?}` does not match the original type `{:?}`",
+            element_type,
+            self.0.type_
+        );
+
+        unsafe {
+            slice::from_raw_parts_mut(self.0.data.raw as *mut u8, self.0.bytes)
+        }
+    }
+
+    /// The flat length of this tensor, the product of all dimensions
+    fn flat_len(&self) -> usize {
+        self.info().dims.iter().product::<i32>() as usize
+    }
+}
+
+/// The `i`th element of packed Int4 data
+fn unpack_int4(packed: &[u8], i: usize) -> i8 {
+    let byte = packed[i / 2];
+    let nibble = if i % 2 == 0 { byte << 4 } else { byte & 0xf0 };
+
+    // Arithmetic shift to sign extend
+    (nibble as i8) >> 4
+}
+
+/// Pack one or two Int4 elements into a byte
+fn pack_int4(pair: &[i8]) -> u8 {
+    for x in pair {
+        assert!((-8..=7).contains(x), "{} is not an Int4", x);
+    }
+
+    let low = pair[0] as u8 & 0x0f;
+    let high = pair.get(1).map_or(0, |&x| x as u8 & 0x0f);
+    low | high << 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int4_roundtrip() {
+        let data = [-8i8, 7, 0, -1, 3];
+        let mut packed = [0u8; 3];
+        for (byte, pair) in packed.iter_mut().zip(data.chunks(2)) {
+            *byte = pack_int4(pair);
+        }
+        assert_eq!(packed, [0x78, 0xf0, 0x03]);
+
+        for (i, &x) in data.iter().enumerate() {
+            assert_eq!(unpack_int4(&packed, i), x);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "8 is not an Int4")]
+    fn int4_out_of_range() {
+        pack_int4(&[8]);
+    }
 }
@@ -0,0 +1,153 @@
+//! Writing tensors in the numpy `.npy` format
+
+use std::io;
+
+use super::{ElementType, Tensor};
+
+impl Tensor {
+    /// Writes this tensor in the numpy `.npy` format
+    ///
+    /// Elements are written as they are stored, with the same shape. Int4
+    /// tensors are unpacked to `int8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails, or with
+    /// `io::ErrorKind::InvalidData` if the element type is not supported.
+    pub fn write_npy<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        let element_type = self.element_type().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                crate::Error::ElementTypeUnimplemented,
+            )
+        })?;
+
+        let descr = npy_descr(element_type);
+        let dims = self.info().dims;
+        let shape = match dims.len() {
+            1 => format!("({},)", dims[0]),
+            _ => {
+                let dims: Vec<String> =
+                    dims.iter().map(|d| d.to_string()).collect();
+                format!("({})", dims.join(", "))
+            }
+        };
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        );
+
+        // The magic string, version, header length and header are padded
+        // with spaces to a multiple of 64 bytes, ending with a newline
+        const PREAMBLE_LEN: usize = 10;
+        let len = PREAMBLE_LEN + header.len() + 1;
+        header.extend(core::iter::repeat(' ').take((64 - len % 64) % 64));
+        header.push('\n');
+
+        writer.write_all(b"\x93NUMPY\x01\x00")?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+
+        if element_type == ElementType::Int4 {
+            let unpacked: Vec<u8> = self.int4_data().map(|x| x as u8).collect();
+            return writer.write_all(&unpacked);
+        }
+
+        let inner = self.inner();
+        let data = unsafe {
+            core::slice::from_raw_parts(
+                inner.data.raw_const as *const u8,
+                inner.bytes,
+            )
+        };
+        writer.write_all(data)
+    }
+}
+
+/// The numpy type descriptor of `element_type`, in the byte order of the
+/// target
+fn npy_descr(element_type: ElementType) -> String {
+    let (kind, size) = match element_type {
+        ElementType::Float32 => ('f', 4),
+        ElementType::UInt8 => ('u', 1),
+        ElementType::Int32 => ('i', 4),
+        ElementType::Int8 => ('i', 1),
+        ElementType::Int16 => ('i', 2),
+        ElementType::UInt16 => ('u', 2),
+        ElementType::Int64 => ('i', 8),
+        ElementType::UInt32 => ('u', 4),
+        ElementType::UInt64 => ('u', 8),
+        ElementType::Bool => ('b', 1),
+        ElementType::Float16 => ('f', 2),
+        ElementType::Float64 => ('f', 8),
+        ElementType::Complex64 => ('c', 8),
+        ElementType::Int4 => ('i', 1),
+    };
+
+    let byte_order = if size == 1 {
+        '|'
+    } else if cfg!(target_endian = "big") {
+        '>'
+    } else {
+        '<'
+    };
+    format!("{}{}{}", byte_order, kind, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_interpreter::MicroInterpreter;
+    use crate::micro_op_resolver::MutableOpResolver;
+    use crate::model::Model;
+
+    #[test]
+    fn npy_descr_byte_order() {
+        assert_eq!(npy_descr(ElementType::Int8), "|i1");
+        assert_eq!(npy_descr(ElementType::Bool), "|b1");
+        if cfg!(target_endian = "little") {
+            assert_eq!(npy_descr(ElementType::Float32), "<f4");
+        }
+    }
+
+    #[test]
+    fn write_npy() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_quantize()
+            .add_fully_connected()
+            .add_dequantize();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+        interpreter.input(0, &[0.5f32]).unwrap();
+
+        let mut npy = Vec::new();
+        interpreter
+            .input_mut(0)
+            .unwrap()
+            .write_npy(&mut npy)
+            .unwrap();
+
+        // Header, padded to a multiple of 64 bytes, then the data
+        assert!(npy.starts_with(b"\x93NUMPY\x01\x00"));
+        assert_eq!(u16::from_le_bytes([npy[8], npy[9]]), 118);
+        let header = std::str::from_utf8(&npy[10..128]).unwrap();
+        assert!(header.starts_with(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (1, 1), }"
+        ));
+        assert!(header.ends_with(" \n"));
+        assert_eq!(&npy[128..], 0.5f32.to_ne_bytes());
+    }
+}
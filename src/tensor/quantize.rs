@@ -0,0 +1,236 @@
+//! Conversion between real values and the data of quantized tensors
+//!
+//! A quantized value `q` represents the real value
+//! `scale * (q - zero_point)`. Quantizing rounds half away from zero, as
+//! TensorFlow does, and saturates to the range of the element type.
+
+use core::convert::TryInto;
+
+use crate::Error;
+
+use super::{ElementType, QuantizationParams, Tensor, TensorInfo};
+
+/// Round half away from zero, without `std`
+fn round(x: f32) -> f32 {
+    // Every float of this magnitude is already an integer
+    if x.is_nan() || x <= -8_388_608.0 || x >= 8_388_608.0 {
+        return x;
+    }
+
+    let truncated = x as i32 as f32;
+    let fraction = x - truncated;
+    if fraction >= 0.5 {
+        truncated + 1.0
+    } else if fraction <= -0.5 {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+/// Quantize `x`, saturating to `min..=max`. NaN quantizes to the zero
+/// point
+fn quantize(x: f32, params: QuantizationParams, min: i64, max: i64) -> i64 {
+    // `as` saturates, and maps NaN to zero. Adding the zero point must
+    // saturate too, as the quotient may be as large as `i64::MAX`
+    let q = round(x / params.scale) as i64;
+    q.saturating_add(params.zero_point.into()).clamp(min, max)
+}
+
+fn dequantize(q: i64, params: QuantizationParams) -> f32 {
+    (q - params.zero_point as i64) as f32 * params.scale
+}
+
+impl Tensor {
+    /// Quantizes `data` into this tensor, using its scale and zero point
+    ///
+    /// Int8, UInt8 and Int16 tensors must be quantized. Data is copied into
+    /// Float32 tensors unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputDataLenMismatch` if the length of `data` does
+    /// not match the flat length of this tensor.
+    ///
+    /// Returns `Error::TensorNotQuantized` if this is an integer tensor
+    /// without quantization parameters.
+    ///
+    /// Returns `Error::ElementTypeUnimplemented` for any other element
+    /// type.
+    pub fn quantize_from(&mut self, data: &[f32]) -> Result<(), Error> {
+        let (element_type, params) = self.quantization()?;
+
+        match element_type {
+            ElementType::Float32 => {
+                copy_quantized(self.as_data_mut(), data, |x| x)
+            }
+            ElementType::Int8 => {
+                let params = params.ok_or(Error::TensorNotQuantized)?;
+                copy_quantized(self.as_data_mut::<i8>(), data, |x| {
+                    quantize(x, params, i8::MIN.into(), i8::MAX.into()) as i8
+                })
+            }
+            ElementType::UInt8 => {
+                let params = params.ok_or(Error::TensorNotQuantized)?;
+                copy_quantized(self.as_data_mut::<u8>(), data, |x| {
+                    quantize(x, params, u8::MIN.into(), u8::MAX.into()) as u8
+                })
+            }
+            ElementType::Int16 => {
+                let params = params.ok_or(Error::TensorNotQuantized)?;
+                copy_quantized(self.as_data_mut::<i16>(), data, |x| {
+                    quantize(x, params, i16::MIN.into(), i16::MAX.into()) as i16
+                })
+            }
+            _ => Err(Error::ElementTypeUnimplemented),
+        }
+    }
+
+    /// Dequantizes this tensor into `data`, using its scale and zero point
+    ///
+    /// Float32 tensors are copied unchanged.
+    ///
+    /// # Errors
+    ///
+    /// As for [`quantize_from`](Self::quantize_from).
+    pub fn dequantize_into(&self, data: &mut [f32]) -> Result<(), Error> {
+        let dequantized = self.dequantized()?;
+        if dequantized.len() != data.len() {
+            return Err(Error::InputDataLenMismatch);
+        }
+
+        for (x, y) in data.iter_mut().zip(dequantized) {
+            *x = y;
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over the dequantized values of this tensor
+    ///
+    /// # Errors
+    ///
+    /// As for [`quantize_from`](Self::quantize_from), except that the
+    /// length always matches.
+    pub fn dequantized(
+        &self,
+    ) -> Result<impl ExactSizeIterator<Item = f32> + '_, Error> {
+        let (element_type, params) = self.quantization()?;
+
+        #[derive(Clone, Copy)]
+        enum Data<'t> {
+            Float32(&'t [f32]),
+            Int8(&'t [i8]),
+            UInt8(&'t [u8]),
+            Int16(&'t [i16]),
+        }
+
+        let data = match element_type {
+            ElementType::Float32 => Data::Float32(self.as_data()),
+            ElementType::Int8 => Data::Int8(self.as_data()),
+            ElementType::UInt8 => Data::UInt8(self.as_data()),
+            ElementType::Int16 => Data::Int16(self.as_data()),
+            _ => return Err(Error::ElementTypeUnimplemented),
+        };
+        // Float32 tensors need no parameters
+        let params = match data {
+            Data::Float32(_) => QuantizationParams {
+                scale: 1.0,
+                zero_point: 0,
+            },
+            _ => params.ok_or(Error::TensorNotQuantized)?,
+        };
+
+        let len = match data {
+            Data::Float32(d) => d.len(),
+            Data::Int8(d) => d.len(),
+            Data::UInt8(d) => d.len(),
+            Data::Int16(d) => d.len(),
+        };
+        Ok((0..len).map(move |i| match data {
+            Data::Float32(d) => d[i],
+            Data::Int8(d) => dequantize(d[i].into(), params),
+            Data::UInt8(d) => dequantize(d[i].into(), params),
+            Data::Int16(d) => dequantize(d[i].into(), params),
+        }))
+    }
+
+    /// The element type and per-tensor quantization parameters
+    fn quantization(
+        &self,
+    ) -> Result<(ElementType, Option<QuantizationParams>), Error> {
+        let info: TensorInfo = self.inner().try_into()?;
+        Ok((info.element_type, info.quantization))
+    }
+}
+
+/// Copy `data` into `tensor`, converting each element with `f`
+fn copy_quantized<T>(
+    tensor: &mut [T],
+    data: &[f32],
+    f: impl Fn(f32) -> T,
+) -> Result<(), Error> {
+    if tensor.len() != data.len() {
+        return Err(Error::InputDataLenMismatch);
+    }
+
+    for (q, x) in tensor.iter_mut().zip(data) {
+        *q = f(*x);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_half_away_from_zero() {
+        assert_eq!(round(0.5), 1.0);
+        assert_eq!(round(-0.5), -1.0);
+        assert_eq!(round(0.49999997), 0.0);
+        assert_eq!(round(-1.4), -1.0);
+        assert_eq!(round(2.5), 3.0);
+        assert_eq!(round(1e10), 1e10);
+    }
+
+    #[test]
+    fn quantize_saturates() {
+        let params = QuantizationParams {
+            scale: 0.5,
+            zero_point: -128,
+        };
+        let (min, max) = (i8::MIN.into(), i8::MAX.into());
+
+        assert_eq!(quantize(0.0, params, min, max), -128);
+        assert_eq!(quantize(10.25, params, min, max), -107);
+        assert_eq!(quantize(1000.0, params, min, max), 127);
+        assert_eq!(quantize(-1000.0, params, min, max), -128);
+        assert_eq!(quantize(f32::NAN, params, min, max), -128);
+        assert_eq!(quantize(f32::INFINITY, params, min, max), 127);
+
+        assert_eq!(dequantize(-107, params), 10.5);
+    }
+
+    #[test]
+    fn quantize_saturates_positive_zero_point() {
+        let params = QuantizationParams {
+            scale: 0.5,
+            zero_point: 128,
+        };
+        let (min, max) = (u8::MIN.into(), u8::MAX.into());
+
+        assert_eq!(quantize(0.0, params, min, max), 128);
+        assert_eq!(quantize(1e30, params, min, max), 255);
+        assert_eq!(quantize(f32::INFINITY, params, min, max), 255);
+        assert_eq!(quantize(-1e30, params, min, max), 0);
+        assert_eq!(quantize(f32::NEG_INFINITY, params, min, max), 0);
+
+        // A negative zero point saturates at the other end
+        let params = QuantizationParams {
+            scale: 0.5,
+            zero_point: -128,
+        };
+        let (min, max) = (i8::MIN.into(), i8::MAX.into());
+        assert_eq!(quantize(f32::NEG_INFINITY, params, min, max), -128);
+    }
+}
@@ -0,0 +1,444 @@
+//! State kept across invokes, in variable tensors and resource variables
+//!
+//! Streaming models keep state between calls to
+//! [`invoke`](crate::MicroInterpreter::invoke). Recurrent operators such as
+//! LSTM and SVDF use variable tensors, and models exported with
+//! `tf.Variable`s use resource variables, created by `VAR_HANDLE` and
+//! written by `ASSIGN_VARIABLE`.
+//!
+//! [`reset`](crate::MicroInterpreter::reset) clears all of this state, for
+//! example between utterances. Resource variables can also be saved and
+//! restored:
+//!
+//! ```ignore
+//! let mut checkpoint = [0u8; 64];
+//! interpreter.read_resource_variable(0, &mut checkpoint)?;
+//! // ...
+//! interpreter.write_resource_variable(0, &checkpoint)?;
+//! ```
+
+use core::ffi::CStr;
+use core::ptr;
+
+use crate::bindings;
+use crate::micro_interpreter::MicroInterpreter;
+use crate::model::Model;
+use crate::{Error, Status};
+
+cpp! {{
+    #include "tensorflow/lite/micro/micro_resource_variable.h"
+    #include "tensorflow/lite/schema/schema_generated.h"
+    #include "tensorflow/lite/schema/schema_utils.h"
+
+    // MicroResourceVariables has no way to list its variables, and keeps
+    // them private. Access checks don't apply to the arguments of an
+    // explicit instantiation, so the templates below define these friend
+    // functions, which read the private members.
+    //
+    // This depends on the private members of
+    // tensorflow/lite/micro/micro_resource_variable.h in the revision of
+    // tflite-micro in submodules/tflite-micro:
+    //
+    //   int MicroResourceVariables::num_resource_variables_
+    //   MicroResourceVariable* MicroResourceVariables::resource_variables_
+    //
+    // and the fields `const char* container`, `const char* shared_name`,
+    // `size_t bytes` and `void* resource_buffer` of the private struct
+    // MicroResourceVariables::MicroResourceVariable. The static_asserts
+    // fail the build if their types change when updating the submodule.
+    // A renamed or removed member fails the explicit instantiation.
+
+    template <typename A, typename B>
+    struct TfMicroSameType {
+        static const bool value = false;
+    };
+    template <typename A>
+    struct TfMicroSameType<A, A> {
+        static const bool value = true;
+    };
+
+    // The number of resource variables created by VAR_HANDLE operators
+    size_t TfMicroResourceVariablesLen(
+        const tflite::MicroResourceVariables* variables);
+
+    // The names, size and buffer of the resource variable `id`. The buffer
+    // is null until an ASSIGN_VARIABLE operator of the variable is
+    // prepared
+    void TfMicroResourceVariable(
+        const tflite::MicroResourceVariables* variables,
+        size_t id,
+        const char** names,
+        size_t* bytes,
+        void** buffer);
+
+    template <typename Len, Len Member>
+    struct TfMicroResourceVariablesLenAccess {
+        static_assert(
+            TfMicroSameType<Len,
+                            int tflite::MicroResourceVariables::*>::value,
+            "MicroResourceVariables::num_resource_variables_ must be an int");
+
+        friend size_t TfMicroResourceVariablesLen(
+            const tflite::MicroResourceVariables* variables) {
+            return static_cast<size_t>(variables->*Member);
+        }
+    };
+    template struct TfMicroResourceVariablesLenAccess<
+        decltype(&tflite::MicroResourceVariables::num_resource_variables_),
+        &tflite::MicroResourceVariables::num_resource_variables_>;
+
+    template <typename Variables, Variables Member>
+    struct TfMicroResourceVariableAccess {
+        friend void TfMicroResourceVariable(
+            const tflite::MicroResourceVariables* variables,
+            size_t id,
+            const char** names,
+            size_t* bytes,
+            void** buffer) {
+            const auto& variable = (variables->*Member)[id];
+            static_assert(
+                TfMicroSameType<decltype(variable.container),
+                                const char*>::value &&
+                TfMicroSameType<decltype(variable.shared_name),
+                                const char*>::value &&
+                TfMicroSameType<decltype(variable.bytes), size_t>::value &&
+                TfMicroSameType<decltype(variable.resource_buffer),
+                                void*>::value,
+                "MicroResourceVariable fields must match TfMicroResourceVariable");
+
+            names[0] = variable.container;
+            names[1] = variable.shared_name;
+            *bytes = variable.bytes;
+            *buffer = variable.resource_buffer;
+        }
+    };
+    template struct TfMicroResourceVariableAccess<
+        decltype(&tflite::MicroResourceVariables::resource_variables_),
+        &tflite::MicroResourceVariables::resource_variables_>;
+}}
+
+/// A resource variable of a model, see
+/// [`MicroInterpreter::resource_variables`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResourceVariable<'m> {
+    /// The handle of the variable, used to read and write it
+    pub handle: usize,
+    /// Container of the variable, if any
+    pub container: Option<&'m str>,
+    /// Name of the variable, shared by its `VAR_HANDLE` operators
+    pub shared_name: Option<&'m str>,
+    /// Size of the variable in bytes
+    pub bytes: usize,
+}
+
+impl Model {
+    /// Returns the number of `VAR_HANDLE` operators in this model, which is
+    /// at least the number of resource variables
+    pub(crate) fn var_handles_len(&self) -> usize {
+        let model = self;
+        unsafe {
+            cpp!([model as "const tflite::Model*"] -> usize as "size_t" {
+                size_t n = 0;
+                auto subgraphs = model->subgraphs();
+                for (size_t s = 0; subgraphs != nullptr && s < subgraphs->size(); s++) {
+                    auto operators = subgraphs->Get(s)->operators();
+                    for (size_t i = 0; operators != nullptr && i < operators->size(); i++) {
+                        auto code = model->operator_codes()->Get(
+                            operators->Get(i)->opcode_index());
+                        if (tflite::GetBuiltinCode(code)
+                                == tflite::BuiltinOperator_VAR_HANDLE) {
+                            n++;
+                        }
+                    }
+                }
+                return n;
+            })
+        }
+    }
+}
+
+impl<'a> MicroInterpreter<'a> {
+    /// Resets the state of the model, as when the interpreter was created
+    ///
+    /// Variable tensors and resource variables are cleared, and each
+    /// operator is reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns a TensorFlow [`Status`](crate::Status) if an error occours in
+    /// TensorFlow.
+    pub fn reset(&mut self) -> Result<(), Status> {
+        let interpreter = self.micro_interpreter;
+
+        let status = unsafe {
+            cpp!([interpreter as "tflite::MicroInterpreter*"]
+                  -> bindings::TfLiteStatus as "TfLiteStatus" {
+                return interpreter->Reset();
+            })
+        };
+
+        match status.into() {
+            Status::Ok => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    /// Returns an iterator over the resource variables of the model, by
+    /// handle
+    ///
+    /// Variables that are never assigned have no storage, and are skipped.
+    pub fn resource_variables(
+        &self,
+    ) -> impl Iterator<Item = ResourceVariable<'a>> + '_ {
+        (0..self.resource_variables_len()).filter_map(move |handle| {
+            let (variable, buffer) = self.resource_variable(handle);
+            if buffer.is_null() {
+                None
+            } else {
+                Some(variable)
+            }
+        })
+    }
+
+    /// Copies the value of the resource variable `handle` into `data`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidResourceVariable` if there is no resource
+    /// variable with `handle`, or it has no storage, see
+    /// [`resource_variables`](Self::resource_variables).
+    ///
+    /// Returns `Error::InputDataLenMismatch` if the length of `data` is not
+    /// the size of the variable.
+    pub fn read_resource_variable(
+        &self,
+        handle: usize,
+        data: &mut [u8],
+    ) -> Result<(), Error> {
+        let buffer = self.resource_variable_buffer(handle, data.len())?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(buffer, data.as_mut_ptr(), data.len());
+        }
+        Ok(())
+    }
+
+    /// Sets the value of the resource variable `handle` from `data`, for
+    /// example to restore a value saved with
+    /// [`read_resource_variable`](Self::read_resource_variable)
+    ///
+    /// # Errors
+    ///
+    /// As for [`read_resource_variable`](Self::read_resource_variable).
+    pub fn write_resource_variable(
+        &mut self,
+        handle: usize,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let buffer = self.resource_variable_buffer(handle, data.len())?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+        }
+        Ok(())
+    }
+
+    /// Returns the number of resource variables, including any without
+    /// storage
+    fn resource_variables_len(&self) -> usize {
+        let resource_variables = self.resource_variables;
+        if resource_variables.is_null() {
+            return 0;
+        }
+
+        unsafe {
+            cpp!([resource_variables as "const tflite::MicroResourceVariables*"]
+                  -> usize as "size_t" {
+                return TfMicroResourceVariablesLen(resource_variables);
+            })
+        }
+    }
+
+    /// The resource variable `handle`, which must exist, and its storage in
+    /// the tensor arena. The storage is null if the variable is never
+    /// assigned
+    fn resource_variable(
+        &self,
+        handle: usize,
+    ) -> (ResourceVariable<'a>, *mut u8) {
+        let resource_variables = self.resource_variables;
+        let mut names: [*const cty::c_char; 2] = [ptr::null(); 2];
+        let mut bytes = 0usize;
+        let mut buffer: *mut u8 = ptr::null_mut();
+        let names_ref = &mut names;
+        let bytes_ref = &mut bytes;
+        let buffer_ref = &mut buffer;
+
+        unsafe {
+            cpp!([resource_variables as "const tflite::MicroResourceVariables*",
+                  handle as "size_t",
+                  names_ref as "const char**",
+                  bytes_ref as "size_t*",
+                  buffer_ref as "void**"] {
+                TfMicroResourceVariable(resource_variables, handle, names_ref,
+                                        bytes_ref, buffer_ref);
+            })
+        };
+
+        // The names are those of the `VAR_HANDLE` operator in the model.
+        // Names that are not valid UTF8 are omitted
+        let name = |name: *const cty::c_char| {
+            if name.is_null() {
+                return None;
+            }
+            unsafe { CStr::from_ptr(name) }.to_str().ok()
+        };
+        let variable = ResourceVariable {
+            handle,
+            container: name(names[0]),
+            shared_name: name(names[1]),
+            bytes,
+        };
+        (variable, buffer)
+    }
+
+    /// The storage of the resource variable `handle`, if its size is `len`
+    /// bytes
+    fn resource_variable_buffer(
+        &self,
+        handle: usize,
+        len: usize,
+    ) -> Result<*mut u8, Error> {
+        if handle >= self.resource_variables_len() {
+            return Err(Error::InvalidResourceVariable);
+        }
+
+        // Reading a variable before it has storage would copy from null
+        let (variable, buffer) = self.resource_variable(handle);
+        if buffer.is_null() {
+            return Err(Error::InvalidResourceVariable);
+        }
+        if variable.bytes != len {
+            return Err(Error::InputDataLenMismatch);
+        }
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_op_resolver::MutableOpResolver;
+
+    #[test]
+    fn reset() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model
+        let model = include_bytes!("../submodules/tflite-micro/tensorflow/lite/micro/examples/hello_world/models/hello_world_float.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty().add_fully_connected();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+        let y = interpreter.output(0).unwrap().as_data::<f32>()[0];
+
+        // The model has no state, so gives the same result after a reset
+        interpreter.reset().unwrap();
+        interpreter.input(0, &[0.5f32]).unwrap();
+        interpreter.invoke().unwrap();
+        assert_eq!(interpreter.output(0).unwrap().as_data::<f32>(), [y]);
+
+        // Nor any resource variables
+        assert_eq!(model.var_handles_len(), 0);
+        assert_eq!(interpreter.resource_variables().count(), 0);
+        assert_eq!(
+            interpreter.read_resource_variable(0, &mut [0u8; 4]).err(),
+            Some(Error::InvalidResourceVariable)
+        );
+        assert_eq!(
+            interpreter.write_resource_variable(0, &[0u8; 4]).err(),
+            Some(Error::InvalidResourceVariable)
+        );
+    }
+
+    /// Invoke the accumulate model with `x`, returning `y`
+    fn accumulate(interpreter: &mut MicroInterpreter, x: [f32; 2]) -> [f32; 2] {
+        interpreter.input(0, &x).unwrap();
+        interpreter.invoke().unwrap();
+        let y = interpreter.output(0).unwrap().as_data::<f32>();
+        [y[0], y[1]]
+    }
+
+    #[test]
+    fn checkpoint() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        // model, y = accumulator + x, then accumulator = y
+        let model = include_bytes!("../tests/models/accumulate.tflite");
+        let model = Model::from_buffer(&model[..]).unwrap();
+        assert_eq!(model.var_handles_len(), 2);
+
+        // resolver
+        let op_resolver = MutableOpResolver::empty()
+            .add_var_handle()
+            .add_read_variable()
+            .add_add()
+            .add_assign_variable();
+
+        // arena
+        const TENSOR_ARENA_SIZE: usize = 4 * 1024;
+        let mut tensor_arena: [u8; TENSOR_ARENA_SIZE] = [0; TENSOR_ARENA_SIZE];
+
+        let mut interpreter =
+            MicroInterpreter::new(&model, op_resolver, &mut tensor_arena[..])
+                .unwrap();
+
+        assert_eq!(accumulate(&mut interpreter, [1.0, 2.0]), [1.0, 2.0]);
+        assert_eq!(accumulate(&mut interpreter, [1.0, 2.0]), [2.0, 4.0]);
+
+        // The unused variable is never assigned, so has no storage
+        let mut variables = interpreter.resource_variables();
+        assert_eq!(
+            variables.next(),
+            Some(ResourceVariable {
+                handle: 0,
+                container: None,
+                shared_name: Some("accumulator"),
+                bytes: 8,
+            })
+        );
+        assert_eq!(variables.next(), None);
+        assert_eq!(
+            interpreter.read_resource_variable(1, &mut [0u8; 0]).err(),
+            Some(Error::InvalidResourceVariable)
+        );
+        assert_eq!(
+            interpreter.read_resource_variable(0, &mut [0u8; 4]).err(),
+            Some(Error::InputDataLenMismatch)
+        );
+
+        let mut checkpoint = [0u8; 8];
+        interpreter
+            .read_resource_variable(0, &mut checkpoint)
+            .unwrap();
+        assert_eq!(checkpoint[..4], 2.0f32.to_ne_bytes());
+
+        // Reset to zero, then restore the checkpoint
+        interpreter.reset().unwrap();
+        assert_eq!(accumulate(&mut interpreter, [1.0, 2.0]), [1.0, 2.0]);
+
+        interpreter.write_resource_variable(0, &checkpoint).unwrap();
+        assert_eq!(accumulate(&mut interpreter, [1.0, 2.0]), [3.0, 6.0]);
+    }
+}
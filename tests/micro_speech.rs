@@ -4,6 +4,15 @@ use tfmicro::{MicroInterpreter, Model, MutableOpResolver};
 
 use log::info;
 
+/// Scales an audio sample to the int8 input of the model
+///
+/// This truncates toward zero, rather than rounding with the scale and zero
+/// point of the input as `input_f32` would, so that the input bytes stay the
+/// same as those this test has always used.
+fn sample_to_i8(sample: i16) -> i8 {
+    ((sample as f32) / (i16::MAX as f32) * (i8::MAX as f32)) as i8
+}
+
 #[test]
 fn micro_speech() {
     env_logger::init();
@@ -18,18 +27,14 @@ fn micro_speech() {
     let no = hound::WavReader::new(no.as_slice())
         .unwrap()
         .samples::<i16>()
-        .map(|x| {
-            ((x.unwrap() as f32) / (i16::MAX as f32) * (i8::MAX as f32)) as i8
-        })
+        .map(|x| sample_to_i8(x.unwrap()))
         .collect::<Vec<_>>();
     let no = no.as_slice();
 
     let yes = hound::WavReader::new(yes.as_slice())
         .unwrap()
         .samples::<i16>()
-        .map(|x| {
-            ((x.unwrap() as f32) / (i16::MAX as f32) * (i8::MAX as f32)) as i8
-        })
+        .map(|x| sample_to_i8(x.unwrap()))
         .collect::<Vec<_>>();
     let yes = yes.as_slice();
 
@@ -59,10 +64,10 @@ fn micro_speech() {
             .unwrap();
 
     // Check properties of the input sensor
-    assert_eq!([1, 1960], interpreter.input_info(0).dims);
+    assert_eq!([1, 1960], interpreter.input_info(0).unwrap().dims);
     assert_eq!(
         tfmicro::ElementType::Int8,
-        interpreter.input_info(0).element_type
+        interpreter.input_info(0).unwrap().element_type
     );
 
     // -------- 'yes' example --------
@@ -70,7 +75,7 @@ fn micro_speech() {
     interpreter.invoke().unwrap();
 
     // Get output for 'yes'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     dbg!(output_tensor.as_data::<u8>());
@@ -89,7 +94,7 @@ fn micro_speech() {
     interpreter.invoke().unwrap();
 
     // Get output for 'no'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     dbg!(output_tensor.as_data::<u8>());
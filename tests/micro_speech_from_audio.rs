@@ -9,21 +9,16 @@ use log::info;
 fn micro_speech_frontend(
     frontend: &mut Frontend,
     audio_slice: &[i16],
-) -> [u8; 40] {
+) -> [f32; 40] {
     // Run generate_micro_features
     let mut output: [u16; 40] = [0; 40];
     frontend.generate_micro_features(audio_slice, &mut output);
 
-    // Scaling, values derived from those used in the training pipeline. See
+    // In the training pipeline, features are divided by 25.6. See
     // https://github.com/tensorflow/tensorflow/blob/master/tensorflow/lite/micro/examples/micro_speech/micro_features/micro_features_generator.cc
-    let mut scaled_features: [u8; 40] = [0; 40];
+    let mut scaled_features: [f32; 40] = [0.; 40];
     for m in 0..40 {
-        let scaled = ((output[m] as f32 * 2550.) + 3328.) / 6656.;
-        scaled_features[m] = match scaled {
-            x if x < 0. => 0u8,
-            x if x > 255. => 255u8,
-            x => x as u8,
-        };
+        scaled_features[m] = output[m] as f32 / 25.6;
     }
 
     scaled_features
@@ -69,7 +64,7 @@ fn micro_speech_with_audio() {
             .unwrap();
 
     // Check properties of the input sensor
-    assert_eq!([1, 49, 40, 1], interpreter.input_info(0).dims);
+    assert_eq!([1, 49, 40, 1], interpreter.input_info(0).unwrap().dims);
 
     // -------- 'yes' example --------
 
@@ -77,7 +72,7 @@ fn micro_speech_with_audio() {
     let micro_feature = (0..49)
         .map(|n| &yes_1000ms[n * 320..(n * 320) + 480])
         .map(|audio_slice| micro_speech_frontend(&mut frontend, audio_slice))
-        .fold(vec![], |mut acc: Vec<u8>, slice| {
+        .fold(vec![], |mut acc: Vec<f32>, slice| {
             acc.extend(&slice[..]);
             acc
         });
@@ -85,11 +80,12 @@ fn micro_speech_with_audio() {
     assert_eq!(micro_feature.len(), 1960);
 
     // Invoke interpreter
-    interpreter.input(0, &micro_feature).unwrap();
+    // Features are quantized with the scale and zero point of the input
+    interpreter.input_f32(0, &micro_feature).unwrap();
     interpreter.invoke().unwrap();
 
     // Get the output tensor
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     info!("{:?}", output_tensor.as_data::<u8>());
@@ -104,7 +100,7 @@ fn micro_speech_with_audio() {
     let micro_feature = (0..49)
         .map(|n| &no_1000ms[n * 320..(n * 320) + 480])
         .map(|audio_slice| micro_speech_frontend(&mut frontend, audio_slice))
-        .fold(vec![], |mut acc: Vec<u8>, slice| {
+        .fold(vec![], |mut acc: Vec<f32>, slice| {
             acc.extend(&slice[..]);
             acc
         });
@@ -112,11 +108,12 @@ fn micro_speech_with_audio() {
     assert_eq!(micro_feature.len(), 1960);
 
     // Invoke interpreter
-    interpreter.input(0, &micro_feature).unwrap();
+    // Features are quantized with the scale and zero point of the input
+    interpreter.input_f32(0, &micro_feature).unwrap();
     interpreter.invoke().unwrap();
 
     // Get the output tensor
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!([1, 4], output_tensor.info().dims);
 
     info!("{:?}", output_tensor.as_data::<u8>());
@@ -0,0 +1,339 @@
+#!/usr/bin/env python3
+"""Writes the small .tflite models used by the tests in this directory, and
+by the tests of the macros crate
+
+The models are written directly as flatbuffers following
+tensorflow/lite/schema/schema.fbs, so neither TensorFlow nor the flatbuffers
+package is needed. Run from anywhere:
+
+    python3 tests/models/make_models.py
+"""
+
+import os
+import struct
+
+# TensorType
+FLOAT32 = 0
+RESOURCE = 13
+
+# BuiltinOperator
+ADD = 0
+DEPTHWISE_CONV_2D = 4
+FULLY_CONNECTED = 9
+RESHAPE = 22
+SOFTMAX = 25
+CUSTOM = 32
+VAR_HANDLE = 142
+READ_VARIABLE = 143
+ASSIGN_VARIABLE = 144
+
+# BuiltinOptions
+ADD_OPTIONS = 11
+VAR_HANDLE_OPTIONS = 111
+
+
+class Table:
+    """A table, with fields given as (kind, value) in schema order. A field
+    of None is absent"""
+
+    def __init__(self, *fields):
+        self.fields = fields
+
+
+class Vector:
+    """A vector of `kind`, which is a struct format character or "offset"
+    for tables and strings"""
+
+    def __init__(self, kind, items):
+        self.kind = kind
+        self.items = list(items)
+
+
+class String:
+    def __init__(self, value):
+        self.value = value.encode()
+
+
+class Builder:
+    """Lays out objects front to back, so every offset points forwards"""
+
+    def __init__(self):
+        self.buf = bytearray()
+
+    def align(self, n):
+        self.buf.extend(b"\0" * (-len(self.buf) % n))
+
+    def finish(self, root, identifier=b"TFL3"):
+        self.buf.extend(b"\0" * 4 + identifier)
+        self.patch(0, root)
+        return bytes(self.buf)
+
+    def patch(self, at, obj):
+        """Place `obj` and point the offset at `at` to it"""
+        pos = self.place(obj)
+        struct.pack_into("<I", self.buf, at, pos - at)
+
+    def place(self, obj):
+        if isinstance(obj, String):
+            self.align(4)
+            pos = len(self.buf)
+            self.buf.extend(struct.pack("<I", len(obj.value)))
+            self.buf.extend(obj.value + b"\0")
+            return pos
+
+        if isinstance(obj, Vector):
+            size = 4 if obj.kind == "offset" else struct.calcsize(obj.kind)
+            self.align(max(4, size))
+            if size < 4:
+                # The length is 4 bytes before the aligned data
+                self.buf.extend(b"\0" * (-(len(self.buf) + 4) % 16))
+            pos = len(self.buf)
+            self.buf.extend(struct.pack("<I", len(obj.items)))
+            if obj.kind != "offset":
+                for item in obj.items:
+                    self.buf.extend(struct.pack("<" + obj.kind, item))
+                return pos
+
+            slots = len(self.buf)
+            self.buf.extend(b"\0" * 4 * len(obj.items))
+            for i, item in enumerate(obj.items):
+                self.patch(slots + 4 * i, item)
+            return pos
+
+        # Table: the vtable, then the table itself
+        layout = []
+        offset = 4
+        for kind, value in obj.fields:
+            if value is None:
+                layout.append(None)
+                continue
+            size = 4 if kind == "offset" else struct.calcsize(kind)
+            offset += -offset % size
+            layout.append(offset)
+            offset += size
+
+        while layout and layout[-1] is None:
+            layout.pop()
+
+        self.align(2)
+        vtable = len(self.buf)
+        self.buf.extend(struct.pack("<HH", 4 + 2 * len(layout), offset))
+        for field in layout:
+            self.buf.extend(struct.pack("<H", field or 0))
+
+        self.align(4)
+        pos = len(self.buf)
+        self.buf.extend(b"\0" * offset)
+        struct.pack_into("<i", self.buf, pos, pos - vtable)
+
+        children = []
+        for (kind, value), field in zip(obj.fields, layout):
+            if field is None:
+                continue
+            if kind == "offset":
+                children.append((pos + field, value))
+            else:
+                struct.pack_into("<" + kind, self.buf, pos + field, value)
+        for at, child in children:
+            self.patch(at, child)
+        return pos
+
+
+def ints(items):
+    return ("offset", Vector("i", items))
+
+
+def string(value):
+    return ("offset", String(value)) if value is not None else (None, None)
+
+
+def operator_code(builtin, custom=None):
+    return Table(
+        ("b", min(builtin, 127)),  # deprecated_builtin_code
+        string(custom),  # custom_code
+        ("i", 1),  # version
+        ("i", builtin),  # builtin_code
+    )
+
+
+def tensor(name, shape, type=FLOAT32):
+    return Table(
+        ints(shape),  # shape
+        ("b", type),  # type
+        ("I", 0),  # buffer, the empty buffer
+        string(name),  # name
+    )
+
+
+def operator(
+    opcode_index, inputs, outputs, builtin_options=None, custom_options=None
+):
+    """`builtin_options` is a (BuiltinOptions, Table) pair"""
+    options_type, options = (None, None), (None, None)
+    if builtin_options is not None:
+        options_type = ("B", builtin_options[0])
+        options = ("offset", builtin_options[1])
+    if custom_options is not None:
+        custom_options = ("offset", Vector("B", custom_options))
+    return Table(
+        ("I", opcode_index),  # opcode_index
+        ints(inputs),  # inputs
+        ints(outputs),  # outputs
+        options_type,  # builtin_options_type
+        options,  # builtin_options
+        custom_options or (None, None),  # custom_options
+    )
+
+
+def signature_def(key, inputs, outputs, subgraph_index=0):
+    """`inputs` and `outputs` map names to tensor indices"""
+
+    def tensor_maps(names):
+        maps = [Table(string(n), ("I", i)) for n, i in names.items()]
+        return ("offset", Vector("offset", maps))
+
+    return Table(
+        tensor_maps(inputs),  # inputs
+        tensor_maps(outputs),  # outputs
+        string(key),  # signature_key
+        (None, None),  # deprecated_tag
+        ("I", subgraph_index),  # subgraph_index
+    )
+
+
+def model(
+    operator_codes, tensors, inputs, outputs, operators, signature_defs=()
+):
+    subgraph = Table(
+        ("offset", Vector("offset", tensors)),  # tensors
+        ints(inputs),  # inputs
+        ints(outputs),  # outputs
+        ("offset", Vector("offset", operators)),  # operators
+        string("main"),  # name
+    )
+    return Table(
+        ("I", 3),  # version
+        ("offset", Vector("offset", operator_codes)),  # operator_codes
+        ("offset", Vector("offset", [subgraph])),  # subgraphs
+        string("tfmicro test model"),  # description
+        ("offset", Vector("offset", [Table()])),  # buffers
+        (None, None),  # metadata_buffer
+        (None, None),  # metadata
+        # signature_defs
+        ("offset", Vector("offset", signature_defs))
+        if signature_defs
+        else (None, None),
+    )
+
+
+def custom_scale():
+    """output = input * custom_options[0], with the custom operator SCALE"""
+    return model(
+        operator_codes=[operator_code(CUSTOM, "SCALE")],
+        tensors=[tensor("input", [1, 4]), tensor("output", [1, 4])],
+        inputs=[0],
+        outputs=[1],
+        operators=[operator(0, [0], [1], custom_options=[3])],
+    )
+
+
+def signature_add():
+    """Identity = x + y, with tensor names from the converter that differ
+    from the names in the signature. The inputs are in the order y, x"""
+    add_options = Table(("b", 0))  # fused_activation_function, NONE
+    return model(
+        operator_codes=[operator_code(ADD)],
+        tensors=[
+            tensor("serving_default_x:0", [1, 2]),
+            tensor("serving_default_y:0", [1, 2]),
+            tensor("StatefulPartitionedCall:0", [1, 2]),
+        ],
+        inputs=[1, 0],
+        outputs=[2],
+        operators=[
+            operator(0, [0, 1], [2], builtin_options=(ADD_OPTIONS, add_options))
+        ],
+        signature_defs=[
+            signature_def(
+                "serving_default",
+                inputs={"x": 0, "y": 1},
+                outputs={"Identity": 2},
+            )
+        ],
+    )
+
+
+def accumulate():
+    """y = accumulator + x, then accumulator = y, with the resource variable
+    accumulator. A second resource variable, unused, is never assigned"""
+    add_options = Table(("b", 0))  # fused_activation_function, NONE
+
+    def var_handle_options(shared_name):
+        options = Table(
+            (None, None),  # container
+            string(shared_name),  # shared_name
+        )
+        return (VAR_HANDLE_OPTIONS, options)
+
+    return model(
+        operator_codes=[
+            operator_code(VAR_HANDLE),
+            operator_code(READ_VARIABLE),
+            operator_code(ADD),
+            operator_code(ASSIGN_VARIABLE),
+        ],
+        tensors=[
+            tensor("x", [1, 2]),
+            tensor("accumulator", [], RESOURCE),
+            tensor("previous", [1, 2]),
+            tensor("y", [1, 2]),
+            tensor("unused", [], RESOURCE),
+        ],
+        inputs=[0],
+        outputs=[3],
+        operators=[
+            operator(
+                0, [], [1], builtin_options=var_handle_options("accumulator")
+            ),
+            operator(1, [1], [2]),
+            operator(2, [2, 0], [3], builtin_options=(ADD_OPTIONS, add_options)),
+            operator(3, [1, 3], []),
+            operator(0, [], [4], builtin_options=var_handle_options("unused")),
+        ],
+    )
+
+
+def operators():
+    """Only operator codes: those of micro_speech, one needing the
+    extended builtin code, and a custom operator"""
+    return model(
+        operator_codes=[
+            operator_code(DEPTHWISE_CONV_2D),
+            operator_code(FULLY_CONNECTED),
+            operator_code(SOFTMAX),
+            operator_code(RESHAPE),
+            operator_code(VAR_HANDLE),
+            operator_code(CUSTOM, "SCALE"),
+        ],
+        tensors=[],
+        inputs=[],
+        outputs=[],
+        operators=[],
+    )
+
+
+# Paths relative to this directory
+MODELS = {
+    "accumulate.tflite": accumulate,
+    "custom_scale.tflite": custom_scale,
+    "signature_add.tflite": signature_add,
+    "../../macros/tests/models/operators.tflite": operators,
+}
+
+if __name__ == "__main__":
+    directory = os.path.dirname(os.path.abspath(__file__))
+    for name, make in MODELS.items():
+        path = os.path.join(directory, name)
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+        with open(path, "wb") as f:
+            f.write(Builder().finish(make()))
@@ -42,7 +42,7 @@ fn person_detection() {
 
     // Check properties of the input sensor
     interpreter.input(0, person).unwrap();
-    assert_eq!([1, 96, 96, 1], interpreter.input_info(0).dims);
+    assert_eq!([1, 96, 96, 1], interpreter.input_info(0).unwrap().dims);
 
     info!("Created setup");
 
@@ -50,7 +50,7 @@ fn person_detection() {
     interpreter.invoke().unwrap();
 
     // get output for 'person'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!(
         [1, 2],
         output_tensor.info().dims,
@@ -69,7 +69,7 @@ fn person_detection() {
     interpreter.invoke().unwrap();
 
     // get output for 'no person'
-    let output_tensor = interpreter.output(0);
+    let output_tensor = interpreter.output(0).unwrap();
     assert_eq!(
         [1, 1, 1, 3],
         output_tensor.info().dims,